@@ -1,6 +1,5 @@
 for area in $(fd --type d --max-depth 1 .); do
 	echo $area
-	node ~/Projects/gobbldygook/modules/gob-hanson-format/cli.js \
-		< "$area/source.yaml" \
+	cargo run --quiet -- expand "$area/source.yaml" \
 		> "$area/source.json"
 done
@@ -0,0 +1,159 @@
+use crate::evaluate::Course;
+use crate::path::RequirementPath;
+use serde_derive::{Deserialize, Serialize};
+
+/// A record of a requirement using up a course
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Claim {
+    pub course: Course,
+    /// The requirement that claimed the course
+    pub claimant: RequirementPath,
+}
+
+/// Keeps track of which requirement has used up which course while an area is evaluated.
+///
+/// A course that has been claimed by one requirement is unavailable to the requirements that
+/// come after it, unless they share a parent that allows its children to share courses.
+#[derive(Debug, Clone, Default)]
+pub struct ClaimTracker {
+    claims: Vec<Claim>,
+    /// Courses that have been set aside for a particular requirement ahead of time
+    reservations: Vec<Claim>,
+}
+
+impl ClaimTracker {
+    /// Creates a tracker where each of the reserved courses may only be used by its claimant
+    pub fn with_reservations(reservations: &[Claim]) -> ClaimTracker {
+        ClaimTracker {
+            claims: vec![],
+            reservations: reservations.to_vec(),
+        }
+    }
+
+    /// Records that the requirement at `path` used up `courses`.
+    ///
+    /// Courses that one of the requirement's own children has already claimed are skipped, so
+    /// that a parent which counts its children's courses doesn't claim them a second time.
+    /// Returns the newly-claimed courses.
+    pub fn claim(&mut self, path: &RequirementPath, courses: &[Course]) -> Vec<Course> {
+        let mut claimed = vec![];
+
+        for course in courses {
+            let already_claimed = self
+                .claims
+                .iter()
+                .any(|c| &c.course == course && c.claimant.is_within(path));
+
+            if !already_claimed {
+                self.claims.push(Claim {
+                    course: course.clone(),
+                    claimant: path.clone(),
+                });
+                claimed.push(course.clone());
+            }
+        }
+
+        claimed
+    }
+
+    /// The courses claimed by the requirement at `path`, or by any of its descendants
+    pub fn claimed_within(&self, path: &RequirementPath) -> Vec<Course> {
+        let mut courses: Vec<Course> = self
+            .claims
+            .iter()
+            .filter(|c| c.claimant.is_within(path))
+            .map(|c| c.course.clone())
+            .collect();
+
+        courses.sort();
+        courses.dedup();
+        courses
+    }
+
+    /// The courses that have been reserved for requirements other than the one at `path`.
+    ///
+    /// A course reserved for a requirement is still available to that requirement's ancestors
+    /// and descendants.
+    pub fn reserved_elsewhere(&self, path: &RequirementPath) -> Vec<Course> {
+        self.reservations
+            .iter()
+            .filter(|r| !r.claimant.is_within(path) && !path.is_within(&r.claimant))
+            .map(|r| r.course.clone())
+            .collect()
+    }
+
+    /// The first claim on `course` made from outside of the requirement at `path`
+    pub fn claim_outside(&self, path: &RequirementPath, course: &Course) -> Option<&Claim> {
+        self.claims
+            .iter()
+            .find(|c| &c.course == course && !c.claimant.is_within(path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ClaimTracker;
+    use crate::course::course;
+    use crate::evaluate::{evaluate_area, Course};
+    use crate::hanson::parse_area;
+    use crate::path::RequirementPath;
+    use std::collections::BTreeMap;
+
+    fn path(path: &str) -> RequirementPath {
+        path.parse().unwrap()
+    }
+
+    #[test]
+    fn parents_do_not_reclaim_their_childrens_courses() {
+        let mut tracker = ClaimTracker::default();
+        let cs1 = course("CSCI", 121);
+        let used = vec![cs1.clone()];
+
+        tracker.claim(&path("Major/Core/CS1"), &used);
+        let claimed = tracker.claim(&path("Major/Core"), &used);
+        assert!(claimed.is_empty());
+
+        let claimed = tracker.claim(&path("Major/Elective"), &used);
+        assert_eq!(claimed, used);
+
+        assert_eq!(tracker.claimed_within(&path("Major/Core")), used);
+        assert_eq!(
+            tracker
+                .claim_outside(&path("Major/Elective"), &cs1)
+                .map(|c| c.claimant.clone()),
+            Some(path("Major/Core/CS1"))
+        );
+    }
+
+    fn evaluate(source: &str, courses: &[Course]) -> Vec<bool> {
+        let area = parse_area(source).unwrap();
+        let result = evaluate_area(courses, &BTreeMap::new(), &BTreeMap::new(), area, &[]);
+
+        result.children[0]
+            .children
+            .iter()
+            .map(|r| r.evaluated.as_ref().unwrap().success)
+            .collect()
+    }
+
+    #[test]
+    fn siblings_only_share_courses_when_allowed() {
+        let courses = vec![course("CSCI", 121), course("CSCI", 125)];
+
+        let exclusive = "
+name: Example
+type: major
+revision: 2018-19
+result: Core
+
+Core:
+    First: CSCI 121 | CSCI 125
+    Second: CSCI 121
+    result: all of (First, Second)
+";
+        assert_eq!(evaluate(exclusive, &courses), vec![true, false]);
+
+        let shared = format!("{}    children share courses: true\n", exclusive);
+        assert_eq!(evaluate(&shared, &courses), vec![true, true]);
+    }
+}
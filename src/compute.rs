@@ -1,34 +1,51 @@
-use crate::evaluate::{Course, CourseList, Requirement};
+use crate::course::Credits;
+use crate::evaluate::{filter_by_where_clause, Course, CourseList, Requirement};
+use crate::expression::counter::{ExpressionCounter, Operator};
+use crate::expression::modifier::WhatEnum;
+use crate::expression::qualification::Qualification;
 use crate::expression::{
     BooleanAndExpression, BooleanOrExpression, CourseExpression, HansonExpression,
-    ReferenceExpression,
+    ModifierExpression, OccurrenceExpression, OfExpression, ReferenceExpression, WhereExpression,
 };
+use crate::progress::Progress;
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone)]
 pub struct ExpressionResult {
     pub matched_courses: Vec<Course>,
     pub success: bool,
+    pub progress: Progress,
+}
+
+/// Removes the courses that another requirement has already claimed
+fn unclaimed(mut courses: CourseList, dirty: &[Course]) -> CourseList {
+    courses.retain(|c| !dirty.contains(c));
+    courses
 }
 
 fn expr_course(
-    expression: CourseExpression,
+    expression: &CourseExpression,
     courses: CourseList,
-    _dirty: Vec<Course>,
+    dirty: Vec<Course>,
     _is_needed: bool,
 ) -> ExpressionResult {
-    // TODO: … why does ExprCourse return a Vec of courses? Shouldn't it just return a single one?
-
-    let matched_courses: Vec<Course> = courses.into_iter().filter(|c| c == &expression).collect();
-    let success = matched_courses.len() > 0;
+    // a course expression is satisfied by a single enrollment, so it only claims the first one
+    let matched_courses: Vec<Course> = unclaimed(courses, &dirty)
+        .into_iter()
+        .filter(|c| c == expression)
+        .take(1)
+        .collect();
+    let success = !matched_courses.is_empty();
 
     ExpressionResult {
         matched_courses,
         success,
+        progress: Progress::courses(success as usize, 1),
     }
 }
 
 fn expr_boolean_or(
-    expression: BooleanOrExpression,
+    expression: &mut BooleanOrExpression,
     children: &[Requirement],
     courses: CourseList,
     dirty: Vec<Course>,
@@ -36,11 +53,22 @@ fn expr_boolean_or(
 ) -> ExpressionResult {
     let mut matched_courses = vec![];
     let mut have_any_been_true = false;
+    let mut progress: Option<Progress> = None;
 
-    for expr in expression.clone().values {
+    for expr in expression.values.iter_mut() {
         let result = compute_expression(expr, children, courses.clone(), dirty.clone(), None);
 
-        matched_courses.extend_from_slice(&result.matched_courses);
+        // only the first branch to succeed uses up any courses; until one does, the branch
+        // that has come the furthest stands for the whole expression
+        if result.success && !have_any_been_true {
+            matched_courses = result.matched_courses;
+            progress = Some(result.progress);
+        } else if !have_any_been_true {
+            progress = match progress {
+                Some(best) if best.fraction >= result.progress.fraction => Some(best),
+                _ => Some(result.progress),
+            };
+        }
 
         have_any_been_true = have_any_been_true || result.success;
     }
@@ -48,72 +76,377 @@ fn expr_boolean_or(
     ExpressionResult {
         matched_courses,
         success: have_any_been_true,
+        progress: progress.unwrap_or_default(),
     }
 }
 
 fn expr_boolean_and(
-    expression: BooleanAndExpression,
+    expression: &mut BooleanAndExpression,
     children: &[Requirement],
     courses: CourseList,
-    dirty: Vec<Course>,
+    mut dirty: Vec<Course>,
     _is_needed: bool,
 ) -> ExpressionResult {
     let mut matched_courses = vec![];
-    let mut have_all_been_true = false;
+    let mut have_all_been_true = true;
+    let mut progress = Progress::default();
 
-    for expr in expression.clone().values {
+    for expr in expression.values.iter_mut() {
         let result = compute_expression(expr, children, courses.clone(), dirty.clone(), None);
 
+        // a course only counts towards one of the clauses
+        dirty.extend_from_slice(&result.matched_courses);
         matched_courses.extend_from_slice(&result.matched_courses);
 
         have_all_been_true = have_all_been_true && result.success;
+        progress = progress + result.progress;
     }
 
     ExpressionResult {
         matched_courses,
         success: have_all_been_true,
+        progress,
+    }
+}
+
+fn expr_of(
+    expression: &mut OfExpression,
+    children: &[Requirement],
+    courses: CourseList,
+    mut dirty: Vec<Course>,
+    _is_needed: bool,
+) -> ExpressionResult {
+    let mut matched_courses: Vec<Course> = vec![];
+    let mut successes = 0;
+    let mut progresses = vec![];
+
+    let available = expression.of.len();
+    let needed = match expression.count.operator {
+        Operator::Gte | Operator::Eq => expression.count.required(available),
+        Operator::Lte => available,
+    };
+
+    for expr in expression.of.iter_mut() {
+        let result = compute_expression(expr, children, courses.clone(), dirty.clone(), None);
+        progresses.push(result.progress);
+
+        if result.success {
+            successes += 1;
+
+            // the options beyond what was asked for don't use up any courses, and the ones
+            // that do can't share them with the options after them
+            if successes <= needed {
+                dirty.extend_from_slice(&result.matched_courses);
+                matched_courses.extend_from_slice(&result.matched_courses);
+            }
+        }
+    }
+
+    matched_courses.sort();
+    matched_courses.dedup();
+
+    let success = expression.count.is_satisfied(successes, available);
+
+    // the expression is as far along as the options that are furthest along, unless more of
+    // them were satisfied than it allows
+    let mut progress: Progress = if successes > expression.count.required(available) {
+        let (have, need) = expression.count.progress(successes, available);
+        Progress::courses(have, need)
+    } else {
+        progresses.sort_by(|a, b| b.fraction.partial_cmp(&a.fraction).unwrap());
+        progresses.into_iter().take(needed).sum()
+    };
+    if success {
+        progress = progress.completed();
+    }
+
+    ExpressionResult {
+        matched_courses,
+        success,
+        progress,
+    }
+}
+
+fn expr_occurrence(
+    expression: &OccurrenceExpression,
+    courses: CourseList,
+    dirty: Vec<Course>,
+    _is_needed: bool,
+) -> ExpressionResult {
+    // each enrollment counts once, no matter how many times it shows up in the course list
+    let mut matched_courses: Vec<Course> = vec![];
+    for course in unclaimed(courses, &dirty)
+        .into_iter()
+        .filter(|c| c == &expression.course)
+    {
+        let already_counted = matched_courses.iter().any(|c| {
+            c.clbid == course.clbid && c.year == course.year && c.semester == course.semester
+        });
+
+        if !already_counted {
+            matched_courses.push(course);
+        }
+    }
+
+    // there is no upper bound on how many times a course could have been taken
+    let count = matched_courses.len();
+    let available = count.max(expression.count.required(count));
+
+    let (have, need) = expression.count.progress(count, available);
+
+    ExpressionResult {
+        success: expression.count.is_satisfied(count, available),
+        progress: Progress::courses(have, need),
+        matched_courses,
+    }
+}
+
+fn expr_where(
+    expression: &mut WhereExpression,
+    courses: CourseList,
+    dirty: Vec<Course>,
+    _is_needed: bool,
+) -> ExpressionResult {
+    // functions like `max(year)` still look at every course, claimed or not
+    let mut matched_courses = filter_by_where_clause(
+        unclaimed(courses.clone(), &dirty),
+        &mut expression.qualification,
+        expression.distinct,
+        Some(courses),
+        None,
+    );
+
+    let count = matched_courses.len();
+    let available = count.max(expression.count.required(count));
+    let success = expression.count.is_satisfied(count, available);
+
+    // only hold on to as many courses as the expression asked for
+    if let Operator::Gte | Operator::Eq = expression.count.operator {
+        matched_courses.truncate(expression.count.required(count));
+    }
+
+    let (have, need) = expression.count.progress(count, available);
+
+    ExpressionResult {
+        success,
+        progress: Progress::courses(have, need),
+        matched_courses,
+    }
+}
+
+/// Counts the courses, credits or departments in `pool` against the modifier's counter
+fn apply_modifier(
+    count: &ExpressionCounter,
+    what: &WhatEnum,
+    besides: &Option<CourseExpression>,
+    mut pool: CourseList,
+) -> ExpressionResult {
+    if let Some(besides) = besides {
+        pool.retain(|c| c != besides);
+    }
+
+    pool.sort();
+    pool.dedup();
+
+    match what {
+        WhatEnum::Course => {
+            let have = pool.len();
+            let success = count.is_satisfied(have, have);
+            let (at, of) = count.progress(have, have);
+
+            if let Operator::Gte | Operator::Eq = count.operator {
+                pool.truncate(count.required(have));
+            }
+
+            ExpressionResult {
+                matched_courses: pool,
+                success,
+                progress: Progress::courses(at, of),
+            }
+        }
+        WhatEnum::Department => {
+            // a cross-listed course only ever stands in for one department
+            let mut departments = BTreeSet::new();
+            let matched_courses: Vec<Course> = pool
+                .into_iter()
+                .filter(
+                    |c| match c.department.iter().find(|d| !departments.contains(*d)) {
+                        Some(department) => departments.insert(department.clone()),
+                        None => false,
+                    },
+                )
+                .collect();
+
+            let have = departments.len();
+            let (at, of) = count.progress(have, have);
+
+            // each department is represented by one of its courses
+            ExpressionResult {
+                matched_courses,
+                success: count.is_satisfied(have, have),
+                progress: Progress::courses(at, of),
+            }
+        }
+        WhatEnum::Credit => {
+            let needed = Credits::whole(count.num.unwrap_or(0));
+            let mut credits = Credits::default();
+            let mut matched_courses = vec![];
+
+            for course in pool {
+                if let Operator::Gte = count.operator {
+                    if credits >= needed {
+                        break;
+                    }
+                }
+
+                credits += course.credit_value();
+                matched_courses.push(course);
+            }
+
+            let success = count.is_satisfied_by_credits(credits);
+
+            // an "at most" counter is as far along as it can be, until it's been exceeded; then,
+            // like an "exactly" counter that's been overshot, it's as far along as the share of
+            // the credits that could be kept
+            let progress = match count.operator {
+                Operator::Lte | Operator::Eq if credits > needed => {
+                    Progress::credits(needed, credits)
+                }
+                Operator::Lte => Progress::credits(needed, needed),
+                Operator::Eq | Operator::Gte => Progress::credits(credits, needed),
+            };
+
+            ExpressionResult {
+                matched_courses,
+                success,
+                progress,
+            }
+        }
+    }
+}
+
+fn expr_modifier(
+    expression: &mut ModifierExpression,
+    children: &[Requirement],
+    courses: CourseList,
+    dirty: Vec<Course>,
+    _is_needed: bool,
+) -> ExpressionResult {
+    let from_children = |refs: &[ReferenceExpression]| -> CourseList {
+        refs.iter()
+            .flat_map(|r| expr_reference(r, children).matched_courses)
+            .collect()
+    };
+
+    // a requirement's filter has already been applied to `courses` by the time we get here,
+    // so "from filter" and "from courses" draw from the same pool. the children's courses were
+    // claimed by the children themselves, so those are always available to count.
+    let pool = unclaimed(courses.clone(), &dirty);
+
+    match expression {
+        ModifierExpression::Where(expr) => {
+            let pool =
+                filter_by_where_clause(pool, &mut expr.qualification, false, Some(courses), None);
+            apply_modifier(&expr.count, &expr.what, &expr.besides, pool)
+        }
+        ModifierExpression::Filter(expr) => {
+            apply_modifier(&expr.count, &expr.what, &expr.besides, pool)
+        }
+        ModifierExpression::FilterWhere(expr) => {
+            let pool =
+                filter_by_where_clause(pool, &mut expr.qualification, false, Some(courses), None);
+            apply_modifier(&expr.count, &expr.what, &expr.besides, pool)
+        }
+        ModifierExpression::Children(expr) => {
+            let pool = from_children(&expr.children);
+            apply_modifier(&expr.count, &expr.what, &expr.besides, pool)
+        }
+        ModifierExpression::ChildrenWhere(expr) => {
+            let pool = from_children(&expr.children);
+            let pool =
+                filter_by_where_clause(pool, &mut expr.qualification, false, Some(courses), None);
+            apply_modifier(&expr.count, &expr.what, &expr.besides, pool)
+        }
     }
 }
 
-fn expr_reference(expression: ReferenceExpression, children: &[Requirement]) -> ExpressionResult {
+fn expr_reference(expression: &ReferenceExpression, children: &[Requirement]) -> ExpressionResult {
     let mut success = false;
     let mut matched_courses = vec![];
+    let mut progress = Progress::default();
 
     if let Some(child) = children.iter().find(|&r| r.name == expression.requirement) {
         if let Some(detail) = &child.evaluated {
             success = detail.success;
             matched_courses = detail.matched_courses.clone();
+            progress = detail.progress;
         }
     }
 
+    // a reference stands for the requirement itself, as well as for everything it needs
     ExpressionResult {
         matched_courses,
         success,
+        progress: progress + Progress::requirements(success as usize, 1),
     }
 }
 
+/// Records the outcome of an expression on the expression itself, so that an evaluated area
+/// shows which clauses were satisfied, and by which courses
+fn annotate(expression: &mut HansonExpression, result: &ExpressionResult) {
+    let (matched_courses, success) = match expression {
+        HansonExpression::BooleanAnd(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::BooleanOr(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::Course(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::Occurrence(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::Of(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::Reference(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::Where(expr) => (&mut expr.matched_courses, &mut expr.result),
+        HansonExpression::Modifier(ModifierExpression::Where(expr)) => {
+            (&mut expr.matched_courses, &mut expr.result)
+        }
+        HansonExpression::Modifier(ModifierExpression::Filter(expr)) => {
+            (&mut expr.matched_courses, &mut expr.result)
+        }
+        HansonExpression::Modifier(ModifierExpression::FilterWhere(expr)) => {
+            (&mut expr.matched_courses, &mut expr.result)
+        }
+        HansonExpression::Modifier(ModifierExpression::Children(expr)) => {
+            (&mut expr.matched_courses, &mut expr.result)
+        }
+        HansonExpression::Modifier(ModifierExpression::ChildrenWhere(expr)) => {
+            (&mut expr.matched_courses, &mut expr.result)
+        }
+    };
+
+    *matched_courses = Some(result.matched_courses.clone());
+    *success = Some(result.success);
+}
+
 pub fn compute_expression(
-    expression: HansonExpression,
+    expression: &mut HansonExpression,
     children: &[Requirement],
     courses: CourseList,
     dirty: Vec<Course>,
-    _fulfillment: Option<Course>,
+    fulfillment: Option<Course>,
 ) -> ExpressionResult {
-    let success = false;
-
-    let default_result = ExpressionResult {
-        matched_courses: vec![],
-        success: success,
-    };
+    // a fulfillment stands in for a course that the student hasn't taken, so from here on down
+    // it's just as available as the ones they have
+    let mut courses = courses;
+    if let Some(fulfillment) = fulfillment {
+        if !courses.contains(&fulfillment) {
+            courses.push(fulfillment);
+        }
+    }
 
-    match expression {
+    let result = match expression {
         HansonExpression::Course(expr) => {
             // println!("{:?}", expr);
             expr_course(expr, courses, dirty, true)
         }
-        HansonExpression::Of(_expr) => {
+        HansonExpression::Of(expr) => {
             // println!("{:?}", expr);
-            default_result
+            expr_of(expr, children, courses, dirty, true)
         }
         HansonExpression::Reference(expr) => {
             // println!("{:?}", expr);
@@ -127,17 +460,445 @@ pub fn compute_expression(
             // println!("{:?}", expr);
             expr_boolean_and(expr, children, courses, dirty, true)
         }
-        HansonExpression::Modifier(_expr) => {
+        HansonExpression::Modifier(expr) => {
             // println!("{:?}", expr);
-            default_result
+            expr_modifier(expr, children, courses, dirty, true)
         }
-        HansonExpression::Occurrence(_expr) => {
+        HansonExpression::Occurrence(expr) => {
             // println!("{:?}", expr);
-            default_result
+            expr_occurrence(expr, courses, dirty, true)
         }
-        HansonExpression::Where(_expr) => {
+        HansonExpression::Where(expr) => {
             // println!("{:?}", expr);
-            default_result
+            expr_where(expr, courses, dirty, true)
+        }
+    };
+
+    annotate(expression, &result);
+    result
+}
+
+/// Every course that an expression could possibly use, whichever of its branches it takes.
+///
+/// Courses that only reach the expression through a reference or a child requirement are left
+/// out, since those belong to the requirement that they come from.
+pub(crate) fn candidate_courses(expression: &HansonExpression, courses: &[Course]) -> CourseList {
+    let where_clause = |qualification: &Qualification| {
+        filter_by_where_clause(
+            courses.to_vec(),
+            &mut qualification.clone(),
+            false,
+            Some(courses.to_vec()),
+            None,
+        )
+    };
+
+    let mut found: CourseList = match expression {
+        HansonExpression::Course(expr) => courses.iter().filter(|c| *c == expr).cloned().collect(),
+        HansonExpression::Occurrence(expr) => courses
+            .iter()
+            .filter(|c| *c == &expr.course)
+            .cloned()
+            .collect(),
+        HansonExpression::BooleanAnd(expr) => expr
+            .values
+            .iter()
+            .flat_map(|v| candidate_courses(v, courses))
+            .collect(),
+        HansonExpression::BooleanOr(expr) => expr
+            .values
+            .iter()
+            .flat_map(|v| candidate_courses(v, courses))
+            .collect(),
+        HansonExpression::Of(expr) => expr
+            .of
+            .iter()
+            .flat_map(|v| candidate_courses(v, courses))
+            .collect(),
+        HansonExpression::Where(expr) => where_clause(&expr.qualification),
+        HansonExpression::Modifier(ModifierExpression::Where(expr)) => {
+            where_clause(&expr.qualification)
         }
+        HansonExpression::Modifier(ModifierExpression::FilterWhere(expr)) => {
+            where_clause(&expr.qualification)
+        }
+        HansonExpression::Modifier(ModifierExpression::Filter(_)) => courses.to_vec(),
+        HansonExpression::Modifier(ModifierExpression::Children(_))
+        | HansonExpression::Modifier(ModifierExpression::ChildrenWhere(_))
+        | HansonExpression::Reference(_) => vec![],
+    };
+
+    found.sort();
+    found.dedup();
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::course::course;
+    use crate::evaluate::RequirementEvaluation;
+    use crate::expression::counter::Shorthand;
+    use crate::expression::modifier;
+
+    fn course_expr(department: &str, number: i32) -> HansonExpression {
+        HansonExpression::Course(CourseExpression {
+            department: vec![department.to_string()],
+            number,
+            level: None,
+            semester: None,
+            year: None,
+            lab: None,
+            matched_courses: None,
+            result: None,
+        })
+    }
+
+    fn of(count: ExpressionCounter, of: Vec<HansonExpression>) -> HansonExpression {
+        HansonExpression::Of(OfExpression {
+            count,
+            of,
+            matched_courses: None,
+            result: None,
+        })
+    }
+
+    #[test]
+    fn occurrences_count_distinct_enrollments() {
+        let mut fall = course("CSCI", 300);
+        fall.clbid = "1".to_string();
+        let mut spring = course("CSCI", 300);
+        spring.clbid = "2".to_string();
+        spring.semester = 3;
+
+        let courses = vec![
+            fall.clone(),
+            spring.clone(),
+            spring.clone(),
+            course("CSCI", 121),
+        ];
+
+        let occurrence = |num| {
+            HansonExpression::Occurrence(OccurrenceExpression {
+                course: CourseExpression {
+                    department: vec!["CSCI".to_string()],
+                    number: 300,
+                    level: None,
+                    semester: None,
+                    year: None,
+                    lab: None,
+                    matched_courses: None,
+                    result: None,
+                },
+                count: ExpressionCounter {
+                    operator: Operator::Gte,
+                    was: None,
+                    num: Some(num),
+                },
+                matched_courses: None,
+                result: None,
+            })
+        };
+
+        let result = compute_expression(&mut occurrence(2), &[], courses.clone(), vec![], None);
+        assert!(result.success);
+        assert_eq!(result.matched_courses, vec![fall, spring]);
+
+        let result = compute_expression(&mut occurrence(3), &[], courses, vec![], None);
+        assert!(!result.success);
+        assert_eq!(result.progress.courses, (2, 3));
+    }
+
+    fn counter(operator: Operator, num: u32) -> ExpressionCounter {
+        ExpressionCounter {
+            operator,
+            was: None,
+            num: Some(num),
+        }
+    }
+
+    #[test]
+    fn modifier_counts_departments() {
+        let mut crosslisted = course("AS", 250);
+        crosslisted.department.push("HI".to_string());
+
+        let courses = vec![
+            course("CSCI", 121),
+            course("CSCI", 125),
+            course("MATH", 220),
+            crosslisted,
+        ];
+
+        let departments = |num| {
+            HansonExpression::Modifier(ModifierExpression::Filter(modifier::Filter {
+                count: counter(Operator::Gte, num),
+                what: WhatEnum::Department,
+                besides: None,
+                matched_courses: None,
+                result: None,
+            }))
+        };
+
+        let result = compute_expression(&mut departments(3), &[], courses.clone(), vec![], None);
+        assert!(result.success);
+        assert_eq!(result.matched_courses.len(), 3);
+
+        let result = compute_expression(&mut departments(4), &[], courses, vec![], None);
+        assert!(!result.success);
+        assert_eq!(result.progress.courses, (3, 4));
+    }
+
+    #[test]
+    fn modifier_counts_credits_from_children_besides_a_course() {
+        let mut half = course("CSCI", 125);
+        half.credits = ordered_float::OrderedFloat(0.5);
+
+        let child = Requirement {
+            name: "Child".to_string(),
+            result: None,
+            message: None,
+            filter: None,
+            children_share_courses: None,
+            children: vec![],
+            evaluated: Some(RequirementEvaluation {
+                applied_fulfillment: None,
+                matched_courses: vec![course("CSCI", 121), half, course("CSCI", 251)],
+                success: true,
+                overridden: false,
+                progress: Progress::courses(3, 3),
+                credits_matched: Credits::from(2.5),
+                claimed_courses: vec![],
+                unavailable_courses: vec![],
+            }),
+        };
+
+        let credits = |operator, num| {
+            HansonExpression::Modifier(ModifierExpression::Children(modifier::Children {
+                count: counter(operator, num),
+                what: WhatEnum::Credit,
+                besides: Some(CourseExpression {
+                    department: vec!["CSCI".to_string()],
+                    number: 121,
+                    level: None,
+                    semester: None,
+                    year: None,
+                    lab: None,
+                    matched_courses: None,
+                    result: None,
+                }),
+                children: vec![ReferenceExpression {
+                    requirement: "Child".to_string(),
+                    matched_courses: None,
+                    result: None,
+                }],
+                matched_courses: None,
+                result: None,
+            }))
+        };
+
+        let children = vec![child];
+
+        let result = compute_expression(
+            &mut credits(Operator::Gte, 1),
+            &children,
+            vec![],
+            vec![],
+            None,
+        );
+        assert!(result.success);
+
+        let result = compute_expression(
+            &mut credits(Operator::Gte, 2),
+            &children,
+            vec![],
+            vec![],
+            None,
+        );
+        assert!(!result.success);
+        assert_eq!(result.matched_courses.len(), 2);
+
+        let result = compute_expression(
+            &mut credits(Operator::Lte, 1),
+            &children,
+            vec![],
+            vec![],
+            None,
+        );
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn where_compares_against_computed_function_values() {
+        use crate::expression::qualification::*;
+
+        let tagged = |department, number, year, gereq: &str| {
+            let mut c = course(department, number);
+            c.year = year;
+            c.gereqs = vec![gereq.to_string()];
+            c
+        };
+
+        let single = |key, operator, value| {
+            Qualification::Single(SingleQualification {
+                key,
+                value,
+                operator,
+                matched_courses: None,
+                result: None,
+            })
+        };
+
+        let mut expr = HansonExpression::Where(WhereExpression {
+            qualification: Qualification::BooleanAnd(AndQualification {
+                values: vec![
+                    single(
+                        FieldNameEnum::GeReq,
+                        QualificationOperator::Eq,
+                        QualificationValue::String(StringValue {
+                            value: "EIN".to_string(),
+                        }),
+                    ),
+                    single(
+                        FieldNameEnum::Year,
+                        QualificationOperator::Gte,
+                        QualificationValue::Function(FunctionValue {
+                            name: FunctionNameEnum::Min,
+                            prop: FieldNameEnum::Year,
+                            qualifier: Box::new(single(
+                                FieldNameEnum::GeReq,
+                                QualificationOperator::Eq,
+                                QualificationValue::String(StringValue {
+                                    value: "BTS-T".to_string(),
+                                }),
+                            )),
+                            computed_value: None,
+                        }),
+                    ),
+                ],
+                matched_courses: None,
+                result: None,
+            }),
+            count: counter(Operator::Gte, 1),
+            distinct: false,
+            matched_courses: None,
+            result: None,
+        });
+
+        let courses = vec![
+            tagged("CSCI", 263, 2013, "EIN"),
+            tagged("REL", 121, 2014, "BTS-T"),
+            tagged("REL", 122, 2016, "BTS-T"),
+            tagged("PHIL", 252, 2015, "EIN"),
+        ];
+
+        let result = compute_expression(&mut expr, &[], courses.clone(), vec![], None);
+        assert!(result.success);
+        assert_eq!(result.matched_courses, vec![courses[3].clone()]);
+
+        let computed_value = match &expr {
+            HansonExpression::Where(expr) => match &expr.qualification {
+                Qualification::BooleanAnd(and) => match &and.values[1] {
+                    Qualification::Single(q) => match &q.value {
+                        QualificationValue::Function(f) => f.computed_value.clone(),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+        assert_eq!(computed_value, Some(serde_json::json!(2014)));
+    }
+
+    #[test]
+    fn of_counts_satisfied_children() {
+        let courses = vec![course("CSCI", 121), course("CSCI", 125)];
+        let options = vec![
+            course_expr("CSCI", 121),
+            course_expr("CSCI", 125),
+            course_expr("CSCI", 251),
+        ];
+
+        let two = ExpressionCounter {
+            operator: Operator::Gte,
+            was: None,
+            num: Some(2),
+        };
+        let result = compute_expression(
+            &mut of(two, options.clone()),
+            &[],
+            courses.clone(),
+            vec![],
+            None,
+        );
+        assert!(result.success);
+        assert_eq!(result.progress.courses, (2, 2));
+        assert_eq!(result.matched_courses, courses);
+
+        let all = ExpressionCounter {
+            operator: Operator::Eq,
+            was: Some(Shorthand::All),
+            num: None,
+        };
+        let result = compute_expression(&mut of(all, options), &[], courses, vec![], None);
+        assert!(!result.success);
+        assert_eq!(result.progress.courses, (2, 3));
+    }
+
+    #[test]
+    fn options_do_not_share_courses() {
+        let or = HansonExpression::BooleanOr(BooleanOrExpression {
+            values: vec![course_expr("CSCI", 121), course_expr("CSCI", 125)],
+            matched_courses: None,
+            result: None,
+        });
+        let two = ExpressionCounter {
+            operator: Operator::Gte,
+            was: None,
+            num: Some(2),
+        };
+        let mut expr = of(two, vec![course_expr("CSCI", 121), or]);
+
+        let result = compute_expression(&mut expr, &[], vec![course("CSCI", 121)], vec![], None);
+        assert!(!result.success);
+
+        let courses = vec![course("CSCI", 121), course("CSCI", 125)];
+        let result = compute_expression(&mut expr, &[], courses.clone(), vec![], None);
+        assert!(result.success);
+        assert_eq!(result.matched_courses, courses);
+
+        let mut both = HansonExpression::BooleanAnd(BooleanAndExpression {
+            values: vec![course_expr("CSCI", 121), course_expr("CSCI", 121)],
+            matched_courses: None,
+            result: None,
+        });
+        let result = compute_expression(&mut both, &[], vec![course("CSCI", 121)], vec![], None);
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn too_many_options_is_not_complete() {
+        let none = ExpressionCounter {
+            operator: Operator::Eq,
+            was: Some(Shorthand::None),
+            num: Some(0),
+        };
+        let options = vec![course_expr("CSCI", 121), course_expr("CSCI", 125)];
+
+        let result = compute_expression(
+            &mut of(none.clone(), options.clone()),
+            &[],
+            vec![course("CSCI", 121)],
+            vec![],
+            None,
+        );
+        assert!(!result.success);
+        assert_eq!(result.progress.fraction, 0.0);
+        assert_eq!(result.progress.to_string(), "0 of 1 courses (0%)");
+
+        let result = compute_expression(&mut of(none, options), &[], vec![], vec![], None);
+        assert!(result.success);
+        assert_eq!(result.progress.fraction, 1.0);
     }
 }
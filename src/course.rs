@@ -1,4 +1,11 @@
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DayOfWeek {
     Mo,
     Tu,
@@ -9,7 +16,7 @@ pub enum DayOfWeek {
     Su,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Offering {
     pub day: DayOfWeek,
     pub start: String,
@@ -17,7 +24,7 @@ pub struct Offering {
     pub location: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CourseStatus {
     #[serde(rename = "O")]
     Open,
@@ -27,29 +34,290 @@ pub enum CourseStatus {
     Cancelled,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A course, either as a student took it or as it's listed in the catalog.
+///
+/// The catalog-only fields are empty for the courses in a student's record.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Course {
     pub clbid: String,
+    pub credits: ordered_float::OrderedFloat<f32>,
     pub crsid: String,
-    pub credits: f32,
-    pub department: String,
-    pub enrolled: i32,
+    /// The departments that the course is listed under; the catalog writes cross-listed
+    /// departments as one string, like `AS/RE`
+    #[serde(deserialize_with = "deserialize_departments")]
+    pub department: Vec<String>,
+    #[serde(default)]
+    pub gereqs: Vec<String>,
     pub groupid: Option<String>,
     pub grouptype: Option<String>,
-    pub instructors: Vec<String>,
-    pub max: i32,
+    pub section: Option<String>,
     pub level: i32,
     pub number: i32,
-    pub offerings: Vec<Offering>,
-    pub notes: Option<Vec<String>>,
-    pub pn: bool,
-    pub revisions: Vec<serde_json::Value>,
     pub semester: i32,
-    pub term: i32,
     pub year: i32,
+
+    #[serde(default, alias = "name")]
     pub title: Option<String>,
-    #[serde(rename = "type")]
-    pub course_type: String,
-    pub deptnum: String,
-    pub status: CourseStatus,
+    #[serde(default)]
+    pub instructors: Vec<String>,
+    /// Whether the course was taken pass/no-pass
+    #[serde(default)]
+    pub pn: bool,
+    /// The year and semester together, as in `20143`
+    #[serde(default)]
+    pub term: Option<i32>,
+    /// The kind of course, like `Research`, `Lab` or `Seminar`
+    #[serde(default, rename = "type")]
+    pub course_type: Option<String>,
+
+    #[serde(default)]
+    pub offerings: Vec<Offering>,
+    #[serde(default)]
+    pub status: Option<CourseStatus>,
+    #[serde(default)]
+    pub enrolled: Option<i32>,
+    #[serde(default)]
+    pub max: Option<i32>,
+    #[serde(default)]
+    pub notes: Option<Vec<String>>,
+}
+
+impl Course {
+    pub fn is_lab(&self) -> bool {
+        self.course_type.as_deref() == Some("Lab")
+    }
+
+    /// The course's credits, rounded to the nearest hundredth
+    pub fn credit_value(&self) -> Credits {
+        Credits::from(self.credits.into_inner())
+    }
+}
+
+/// A one-credit course, taken in the fall of 2015, for tests to build on
+#[cfg(test)]
+pub(crate) fn course(department: &str, number: i32) -> Course {
+    Course {
+        clbid: format!("{}{}", department, number),
+        credits: ordered_float::OrderedFloat(1.0),
+        crsid: format!("{}{}", department, number),
+        department: vec![department.to_string()],
+        level: number / 100 * 100,
+        number,
+        semester: 1,
+        year: 2015,
+        ..Course::default()
+    }
+}
+
+#[cfg(test)]
+impl Course {
+    /// The same course, taken in another term
+    pub(crate) fn in_term(self, year: i32, semester: i32) -> Course {
+        Course {
+            year,
+            semester,
+            ..self
+        }
+    }
+
+    /// The same course, as a catalog section with the given status
+    pub(crate) fn with_status(self, status: CourseStatus) -> Course {
+        Course {
+            status: Some(status),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Course {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}[{},{}]",
+            self.department.join("/"),
+            self.number,
+            self.year,
+            self.semester
+        )
+    }
+}
+
+/// A number of credits, kept as a whole number of hundredths so that partial credits like 0.25
+/// and 0.5 add up exactly
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Credits(u32);
+
+impl Credits {
+    pub fn whole(credits: u32) -> Credits {
+        Credits(credits * 100)
+    }
+
+    pub fn hundredths(self) -> u32 {
+        self.0
+    }
+
+    /// The total credits of the given courses
+    pub fn of<'a, I: IntoIterator<Item = &'a Course>>(courses: I) -> Credits {
+        courses.into_iter().map(|c| c.credit_value()).sum()
+    }
+}
+
+impl From<f32> for Credits {
+    fn from(credits: f32) -> Credits {
+        Credits((credits.max(0.0) * 100.0).round() as u32)
+    }
+}
+
+impl From<Credits> for f64 {
+    fn from(credits: Credits) -> f64 {
+        f64::from(credits.0) / 100.0
+    }
+}
+
+impl Add for Credits {
+    type Output = Credits;
+
+    fn add(self, other: Credits) -> Credits {
+        Credits(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Credits {
+    fn add_assign(&mut self, other: Credits) {
+        self.0 += other.0;
+    }
+}
+
+/// Takes away as many credits as there are, stopping at zero
+impl Sub for Credits {
+    type Output = Credits;
+
+    fn sub(self, other: Credits) -> Credits {
+        Credits(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Sum for Credits {
+    fn sum<I: Iterator<Item = Credits>>(iter: I) -> Credits {
+        iter.fold(Credits::default(), Add::add)
+    }
+}
+
+impl fmt::Display for Credits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.0 / 100, self.0 % 100) {
+            (whole, 0) => write!(f, "{}", whole),
+            (whole, part) if part % 10 == 0 => write!(f, "{}.{}", whole, part / 10),
+            (whole, part) => write!(f, "{}.{:02}", whole, part),
+        }
+    }
+}
+
+impl Serialize for Credits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(f64::from(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Credits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f32::deserialize(deserializer).map(Credits::from)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Departments {
+    One(String),
+    Many(Vec<String>),
+}
+
+fn deserialize_departments<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let departments = match Departments::deserialize(deserializer)? {
+        Departments::One(department) => vec![department],
+        Departments::Many(departments) => departments,
+    };
+
+    Ok(departments
+        .iter()
+        .flat_map(|d| d.split('/'))
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Course, CourseStatus, Credits};
+
+    #[test]
+    fn reads_student_records() {
+        let course: Course = serde_yaml::from_str(
+            r#"
+"clbid": "0000097582"
+"credits": 1
+"crsid": "0000000059"
+"department": ["ART"]
+"gereqs": ["ALS-A", "MCG"]
+"instructors": ["Karil J. Kucera"]
+"level": 100
+"name": "World Architecture"
+"number": 161
+"pn": false
+"semester": 3
+"term": 20143
+"type": "Research"
+"year": 2014
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(course.title, Some("World Architecture".to_string()));
+        assert_eq!(course.course_type, Some("Research".to_string()));
+        assert_eq!(course.term, Some(20143));
+        assert!(course.offerings.is_empty());
+    }
+
+    #[test]
+    fn reads_catalog_listings() {
+        let course: Course = serde_json::from_str(
+            r#"{
+                "clbid": "0000097999", "crsid": "0000012345", "credits": 1,
+                "department": "AS/RE", "enrolled": 18, "max": 25,
+                "groupid": null, "grouptype": null, "section": "A",
+                "instructors": ["Jane Doe"], "level": 200, "number": 250,
+                "offerings": [{"day": "Mo", "start": "0905", "end": "1000", "location": "TOH 101"}],
+                "pn": false, "semester": 1, "term": 20181, "year": 2018,
+                "title": "Religions of Asia", "type": "Research", "status": "O"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(course.department, vec!["AS", "RE"]);
+        assert_eq!(course.status, Some(CourseStatus::Open));
+        assert_eq!(course.offerings.len(), 1);
+        assert!(!course.is_lab());
+    }
+
+    #[test]
+    fn partial_credits_add_up_exactly() {
+        let quarter = Course {
+            credits: ordered_float::OrderedFloat(0.25),
+            ..Course::default()
+        };
+        let tenth = Course {
+            credits: ordered_float::OrderedFloat(0.1),
+            ..Course::default()
+        };
+
+        let quarters = Credits::of(vec![&quarter; 4]);
+        assert_eq!(quarters, Credits::whole(1));
+
+        let tenths = Credits::of(vec![&tenth; 3]);
+        assert_eq!(tenths.hundredths(), 30);
+        assert_eq!(tenths.to_string(), "0.3");
+        assert_eq!((tenths + Credits::from(0.25)).to_string(), "0.55");
+    }
 }
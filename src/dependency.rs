@@ -0,0 +1,162 @@
+use crate::error::Error;
+use crate::evaluate::{collect_references, AreaOfStudy, Requirement};
+use crate::path::RequirementPath;
+
+/// The positions of the siblings that a requirement refers to.
+///
+/// A requirement's own children take precedence over its siblings, so a reference to a child
+/// that happens to share a sibling's name isn't a dependency on that sibling.
+fn sibling_dependencies(requirement: &Requirement, siblings: &[Requirement]) -> Vec<usize> {
+    let mut references = vec![];
+    if let Some(result) = &requirement.result {
+        collect_references(result, &mut references);
+    }
+
+    references
+        .into_iter()
+        .filter(|name| !requirement.children.iter().any(|c| &c.name == name))
+        .filter_map(|name| siblings.iter().position(|s| s.name == name))
+        .collect()
+}
+
+/// Orders a list of sibling requirements so that each one comes after the siblings it refers
+/// to; requirements that don't depend on each other keep the order they were declared in.
+///
+/// If the siblings refer to each other in a cycle, the positions of the requirements in the
+/// cycle are returned as the error, in the order that they refer to each other.
+pub fn evaluation_order(requirements: &[Requirement]) -> Result<Vec<usize>, Vec<usize>> {
+    let dependencies: Vec<Vec<usize>> = requirements
+        .iter()
+        .map(|r| sibling_dependencies(r, requirements))
+        .collect();
+
+    let mut order: Vec<usize> = vec![];
+    while order.len() < requirements.len() {
+        let next = (0..requirements.len())
+            .find(|i| !order.contains(i) && dependencies[*i].iter().all(|d| order.contains(d)));
+
+        match next {
+            Some(next) => order.push(next),
+            None => {
+                // every remaining requirement waits on another remaining one, so following
+                // the references from any of them will eventually come back around
+                let mut path: Vec<usize> = vec![];
+                let mut current = (0..requirements.len())
+                    .find(|i| !order.contains(i))
+                    .expect("some requirement is still waiting");
+
+                while !path.contains(&current) {
+                    path.push(current);
+                    current = *dependencies[current]
+                        .iter()
+                        .find(|d| !order.contains(d))
+                        .expect("a waiting requirement depends on another waiting one");
+                }
+
+                let start = path.iter().position(|&i| i == current).unwrap();
+                return Err(path.split_off(start));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+fn find_cycles(requirements: &[Requirement], path: &RequirementPath, errors: &mut Vec<Error>) {
+    if let Err(cycle) = evaluation_order(requirements) {
+        errors.push(Error::ReferenceCycle {
+            requirements: cycle
+                .iter()
+                .map(|&i| path.child(&requirements[i].name))
+                .collect(),
+        });
+    }
+
+    for requirement in requirements {
+        find_cycles(
+            &requirement.children,
+            &path.child(&requirement.name),
+            errors,
+        );
+    }
+}
+
+/// Finds every group of sibling requirements that refer to each other in a cycle
+pub fn reference_cycles(area_of_study: &AreaOfStudy) -> Vec<Error> {
+    let mut errors = vec![];
+    find_cycles(
+        &area_of_study.children,
+        &RequirementPath::area(&area_of_study.area_name, &area_of_study.area_type),
+        &mut errors,
+    );
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::{evaluation_order, reference_cycles};
+    use crate::evaluate::Requirement;
+    use crate::expression::{HansonExpression, ReferenceExpression};
+    use crate::hanson::parse_area;
+
+    fn requirement(name: &str, reference: Option<&str>) -> Requirement {
+        Requirement {
+            name: name.to_string(),
+            result: reference.map(|r| {
+                HansonExpression::Reference(ReferenceExpression {
+                    requirement: r.to_string(),
+                    matched_courses: None,
+                    result: None,
+                })
+            }),
+            message: None,
+            filter: None,
+            children_share_courses: None,
+            children: vec![],
+            evaluated: None,
+        }
+    }
+
+    #[test]
+    fn siblings_come_after_what_they_refer_to() {
+        let requirements = vec![
+            requirement("Summary", Some("Core")),
+            requirement("Elective", None),
+            requirement("Core", None),
+        ];
+
+        assert_eq!(evaluation_order(&requirements), Ok(vec![1, 2, 0]));
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let requirements = vec![
+            requirement("Free", None),
+            requirement("A", Some("B")),
+            requirement("B", Some("C")),
+            requirement("C", Some("A")),
+        ];
+
+        assert_eq!(evaluation_order(&requirements), Err(vec![1, 2, 3]));
+
+        let mut area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: A
+A: CSCI 121
+",
+        )
+        .unwrap();
+        area.children = requirements;
+
+        let errors = reference_cycles(&area);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "requirements refer to each other in a cycle: \
+             `Example/major/A` -> `Example/major/B` -> `Example/major/C` -> `Example/major/A`"
+        );
+    }
+}
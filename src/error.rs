@@ -0,0 +1,234 @@
+use crate::hanson::ParseError;
+use crate::path::RequirementPath;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong while loading or evaluating an area of study
+#[derive(Debug)]
+pub enum Error {
+    /// A file couldn't be read
+    Io { path: PathBuf, source: io::Error },
+
+    /// An area's JSON doesn't describe an area of study
+    MalformedArea {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+
+    /// A student's YAML doesn't describe a student
+    MalformedStudent {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+
+    /// A list of courses in YAML doesn't describe courses
+    MalformedCourses {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+
+    /// A catalog course's meeting time couldn't be read
+    MalformedOffering {
+        path: Option<PathBuf>,
+        clbid: String,
+        message: String,
+    },
+
+    /// An area's hand-written source couldn't be parsed
+    Hanson {
+        path: Option<PathBuf>,
+        error: Box<ParseError>,
+    },
+
+    /// An area uses a kind of expression that the examiner doesn't know how to evaluate
+    UnsupportedExpression {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        expression: String,
+    },
+
+    /// A requirement refers to a requirement that is neither one of its children nor one of
+    /// its siblings
+    UnknownReference {
+        requirement: RequirementPath,
+        reference: String,
+    },
+
+    /// Sibling requirements refer to each other in a loop, so none of them can be evaluated
+    /// before the others
+    ReferenceCycle { requirements: Vec<RequirementPath> },
+
+    /// A result couldn't be written out as YAML or JSON
+    Serialize {
+        format: &'static str,
+        message: String,
+    },
+}
+
+impl Error {
+    /// Attaches the name of the file that was being read when the error occurred
+    pub fn in_file(self, file: &Path) -> Error {
+        let file = Some(file.to_path_buf());
+
+        match self {
+            Error::MalformedArea {
+                line,
+                column,
+                message,
+                ..
+            } => Error::MalformedArea {
+                path: file,
+                line,
+                column,
+                message,
+            },
+            Error::MalformedStudent {
+                line,
+                column,
+                message,
+                ..
+            } => Error::MalformedStudent {
+                path: file,
+                line,
+                column,
+                message,
+            },
+            Error::MalformedCourses {
+                line,
+                column,
+                message,
+                ..
+            } => Error::MalformedCourses {
+                path: file,
+                line,
+                column,
+                message,
+            },
+            Error::MalformedOffering { clbid, message, .. } => Error::MalformedOffering {
+                path: file,
+                clbid,
+                message,
+            },
+            Error::Hanson { error, .. } => Error::Hanson { path: file, error },
+            Error::UnsupportedExpression {
+                line, expression, ..
+            } => Error::UnsupportedExpression {
+                path: file,
+                line,
+                expression,
+            },
+            other => other,
+        }
+    }
+}
+
+fn write_location(
+    f: &mut fmt::Formatter,
+    path: &Option<PathBuf>,
+    line: &Option<usize>,
+) -> fmt::Result {
+    match (path, line) {
+        (Some(path), Some(line)) => write!(f, "{}:{}: ", path.display(), line),
+        (Some(path), None) => write!(f, "{}: ", path.display()),
+        (None, Some(line)) => write!(f, "line {}: ", line),
+        (None, None) => Ok(()),
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(f, "unable to read {}: {}", path.display(), source)
+            }
+            Error::MalformedArea {
+                path,
+                line,
+                message,
+                ..
+            } => {
+                write_location(f, path, line)?;
+                write!(f, "malformed area: {}", message)
+            }
+            Error::MalformedStudent {
+                path,
+                line,
+                message,
+                ..
+            } => {
+                write_location(f, path, line)?;
+                write!(f, "malformed student: {}", message)
+            }
+            Error::MalformedCourses {
+                path,
+                line,
+                message,
+                ..
+            } => {
+                write_location(f, path, line)?;
+                write!(f, "malformed courses: {}", message)
+            }
+            Error::MalformedOffering {
+                path,
+                clbid,
+                message,
+            } => {
+                write_location(f, path, &None)?;
+                write!(f, "malformed offering for course {}: {}", clbid, message)
+            }
+            Error::Hanson { path, error } => {
+                write_location(f, path, &error.line)?;
+                write!(f, "{}", error)
+            }
+            Error::UnsupportedExpression {
+                path,
+                line,
+                expression,
+            } => {
+                write_location(f, path, line)?;
+                write!(f, "unsupported expression type `{}`", expression)
+            }
+            Error::UnknownReference {
+                requirement,
+                reference,
+            } => write!(
+                f,
+                "`{}` refers to `{}`, which is neither one of its children nor one of its siblings",
+                requirement, reference
+            ),
+            Error::ReferenceCycle { requirements } => {
+                let names: Vec<String> = requirements
+                    .iter()
+                    .chain(requirements.first())
+                    .map(|r| format!("`{}`", r))
+                    .collect();
+                write!(
+                    f,
+                    "requirements refer to each other in a cycle: {}",
+                    names.join(" -> ")
+                )
+            }
+            Error::Serialize { format, message } => {
+                write!(f, "unable to write {}: {}", format, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
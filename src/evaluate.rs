@@ -1,17 +1,25 @@
+use crate::claim::{Claim, ClaimTracker};
 use crate::compute::compute_expression;
+pub use crate::course::{Course, Credits};
+use crate::dependency;
+use crate::error::Error;
 use crate::expression::counter;
 use crate::expression::course;
 use crate::expression::filter::*;
 use crate::expression::qualification;
+use crate::expression::qualification::{FieldNameEnum, QualificationOperator, QualificationValue};
 use crate::expression::*;
+use crate::path::RequirementPath;
+use crate::progress::Progress;
+use crate::solve::Alternative;
 use serde_derive::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::cmp::Ordering;
 use std::collections::HashSet;
-use std::fmt;
+use std::collections::{BTreeMap, BTreeSet};
 
-pub type OverrideMap = BTreeMap<String, bool>;
+pub type OverrideMap = BTreeMap<RequirementPath, bool>;
 pub type Fulfillment = Course;
-pub type FulfillmentMap = BTreeMap<String, Fulfillment>;
+pub type FulfillmentMap = BTreeMap<RequirementPath, Fulfillment>;
 pub type CourseList = Vec<Course>;
 
 // the input to `evaluate`
@@ -34,9 +42,14 @@ pub struct AreaOfStudy {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AreaOfStudyEvaluation {
-    pub progress: (usize, usize),
+    pub progress: Progress,
     pub error: Option<String>,
     pub success: bool,
+    /// The credits of the courses that the area's result matched
+    pub credits_matched: Credits,
+    /// The assignments of contested courses that were tried while solving the area
+    #[serde(default)]
+    pub alternatives: Vec<Alternative>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -65,54 +78,169 @@ pub struct RequirementEvaluation {
     pub matched_courses: Vec<Course>,
     pub success: bool,
     pub overridden: bool,
+    pub progress: Progress,
+    /// The credits of the courses in `matched_courses`, counting each course once
+    pub credits_matched: Credits,
+    /// The courses that this requirement used up, not counting those used by its children
+    pub claimed_courses: Vec<Course>,
+    /// The courses that this requirement could have used, had another requirement not claimed them
+    pub unavailable_courses: Vec<Claim>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Course {
-    pub clbid: String,
-    pub credits: ordered_float::OrderedFloat<f32>,
-    pub crsid: String,
-    pub department: Vec<String>,
-    pub groupid: Option<String>,
-    pub grouptype: Option<String>,
-    pub section: Option<String>,
-    pub level: i32,
-    pub number: i32,
-    pub semester: i32,
-    pub year: i32,
-}
-
-impl fmt::Display for Course {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{} {}[{},{}]",
-            self.department.join("/"),
-            self.number,
-            self.year,
-            self.semester
-        )
+/// Totals the credits of the courses, counting each course only once
+fn distinct_credits(courses: &[Course]) -> Credits {
+    Credits::of(courses.iter().collect::<BTreeSet<&Course>>())
+}
+
+/// The value of a course's field, as seen by a qualification
+enum FieldValue<'a> {
+    Number(i32),
+    Decimal(f32),
+    Boolean(bool),
+    List(&'a [String]),
+}
+
+fn field_value<'a>(course: &'a Course, key: &FieldNameEnum) -> FieldValue<'a> {
+    match key {
+        FieldNameEnum::GeReq => FieldValue::List(&course.gereqs),
+        FieldNameEnum::Year => FieldValue::Number(course.year),
+        FieldNameEnum::Department => FieldValue::List(&course.department),
+        FieldNameEnum::Level => FieldValue::Number(course.level),
+        FieldNameEnum::Number => FieldValue::Number(course.number),
+        FieldNameEnum::Semester => FieldValue::Number(course.semester),
+        FieldNameEnum::Credits => FieldValue::Decimal(course.credits.into_inner()),
+        FieldNameEnum::Instructors => FieldValue::List(&course.instructors),
+        FieldNameEnum::Type => match &course.course_type {
+            Some(course_type) => FieldValue::List(std::slice::from_ref(course_type)),
+            None => FieldValue::List(&[]),
+        },
+        FieldNameEnum::Pn => FieldValue::Boolean(course.pn),
+        FieldNameEnum::Term => {
+            FieldValue::Number(course.term.unwrap_or(course.year * 10 + course.semester))
+        }
+    }
+}
+
+pub(crate) fn compare_ordering(ordering: Ordering, operator: QualificationOperator) -> bool {
+    match operator {
+        QualificationOperator::Lt => ordering == Ordering::Less,
+        QualificationOperator::Lte => ordering != Ordering::Greater,
+        QualificationOperator::Eq => ordering == Ordering::Equal,
+        QualificationOperator::Neq => ordering != Ordering::Equal,
+        QualificationOperator::Gt => ordering == Ordering::Greater,
+        QualificationOperator::Gte => ordering != Ordering::Less,
+    }
+}
+
+/// Compares a field against a single static value.
+///
+/// Numeric and boolean fields compare by value, and never match values of another type. List
+/// fields are equal to a value when they contain it; they have no order, so `<`, `<=`, `>` and
+/// `>=` never match them.
+fn compare_field(
+    field: &FieldValue,
+    operator: QualificationOperator,
+    value: &serde_json::Value,
+) -> bool {
+    match field {
+        FieldValue::Number(n) => {
+            let value = match value {
+                serde_json::Value::Number(v) => v.as_i64(),
+                serde_json::Value::String(v) => v.parse::<i64>().ok(),
+                _ => None,
+            };
+
+            match value {
+                Some(value) => compare_ordering(i64::from(*n).cmp(&value), operator),
+                None => operator == QualificationOperator::Neq,
+            }
+        }
+        FieldValue::Decimal(n) => {
+            // compared as f32s, so that `credits = 0.1` matches a course worth 0.1 credits
+            let value = match value {
+                serde_json::Value::Number(v) => v.as_f64().map(|v| v as f32),
+                serde_json::Value::String(v) => v.parse::<f32>().ok(),
+                _ => None,
+            };
+
+            match value.and_then(|value| n.partial_cmp(&value)) {
+                Some(ordering) => compare_ordering(ordering, operator),
+                None => operator == QualificationOperator::Neq,
+            }
+        }
+        FieldValue::Boolean(b) => {
+            let value = match value {
+                serde_json::Value::Bool(v) => Some(*v),
+                serde_json::Value::String(v) => v.parse::<bool>().ok(),
+                _ => None,
+            };
+
+            match value {
+                Some(value) => compare_ordering(b.cmp(&value), operator),
+                None => operator == QualificationOperator::Neq,
+            }
+        }
+        FieldValue::List(items) => {
+            let value = match value {
+                serde_json::Value::String(v) => v.clone(),
+                v => v.to_string(),
+            };
+
+            match operator {
+                QualificationOperator::Eq => items.contains(&value),
+                QualificationOperator::Neq => !items.contains(&value),
+                _ => false,
+            }
+        }
+    }
+}
+
+fn compare_qualification(lhs: &qualification::SingleQualification, rhs: &Course) -> bool {
+    let field = field_value(rhs, &lhs.key);
+    let operator = lhs.operator;
+
+    match &lhs.value {
+        QualificationValue::Number(v) => compare_field(&field, operator, &v.value.into()),
+        QualificationValue::String(v) => compare_field(&field, operator, &v.value.clone().into()),
+        // "not one of" and "not all of" read more naturally than "one of not" and "all of not"
+        QualificationValue::BooleanOr(v) => match operator {
+            QualificationOperator::Neq => !v
+                .values
+                .iter()
+                .any(|v| compare_field(&field, QualificationOperator::Eq, v)),
+            _ => v.values.iter().any(|v| compare_field(&field, operator, v)),
+        },
+        QualificationValue::BooleanAnd(v) => match operator {
+            QualificationOperator::Neq => !v
+                .values
+                .iter()
+                .all(|v| compare_field(&field, QualificationOperator::Eq, v)),
+            _ => v.values.iter().all(|v| compare_field(&field, operator, v)),
+        },
+        QualificationValue::Function(f) => match &f.computed_value {
+            Some(value) => compare_field(&field, operator, value),
+            None => false,
+        },
     }
 }
 
 impl PartialEq<Course> for qualification::SingleQualification {
-    fn eq(&self, _other: &Course) -> bool {
-        false
+    fn eq(&self, other: &Course) -> bool {
+        compare_qualification(self, other)
     }
 }
 
 impl PartialEq<qualification::SingleQualification> for Course {
-    fn eq(&self, _other: &qualification::SingleQualification) -> bool {
-        false
+    fn eq(&self, other: &qualification::SingleQualification) -> bool {
+        compare_qualification(other, self)
     }
 }
 
-fn compare_departments(lhs_depts: &Vec<String>, rhs_depts: &Vec<String>) -> bool {
-    let self_depts: HashSet<String> = lhs_depts.clone().into_iter().collect();
-    let other_depts: HashSet<String> = rhs_depts.clone().into_iter().collect();
-    let diff: Vec<&String> = self_depts.symmetric_difference(&other_depts).collect();
+fn compare_departments(lhs_depts: &[String], rhs_depts: &[String]) -> bool {
+    let self_depts: HashSet<&String> = lhs_depts.iter().collect();
+    let other_depts: HashSet<&String> = rhs_depts.iter().collect();
 
-    diff.len() == 0
+    self_depts.symmetric_difference(&other_depts).count() == 0
 }
 
 fn compare_courses(lhs: &course::CourseExpression, rhs: &Course) -> bool {
@@ -125,13 +253,19 @@ fn compare_courses(lhs: &course::CourseExpression, rhs: &Course) -> bool {
     }
 
     if let Some(year) = lhs.year {
-        if year == rhs.year {
+        if year != rhs.year {
             return false;
         }
     }
 
     if let Some(semester) = lhs.semester {
-        if semester == rhs.semester {
+        if semester != rhs.semester {
+            return false;
+        }
+    }
+
+    if let Some(lab) = lhs.lab {
+        if lab != rhs.is_lab() {
             return false;
         }
     }
@@ -141,40 +275,105 @@ fn compare_courses(lhs: &course::CourseExpression, rhs: &Course) -> bool {
 
 impl PartialEq<Course> for course::CourseExpression {
     fn eq(&self, other: &Course) -> bool {
-        compare_courses(&self, &other)
+        compare_courses(self, other)
     }
 }
 
 impl PartialEq<course::CourseExpression> for Course {
     fn eq(&self, other: &course::CourseExpression) -> bool {
-        compare_courses(&other, &self)
+        compare_courses(other, self)
+    }
+}
+
+impl From<&Course> for course::CourseExpression {
+    fn from(course: &Course) -> course::CourseExpression {
+        course::CourseExpression {
+            department: course.department.clone(),
+            number: course.number,
+            level: None,
+            semester: Some(course.semester),
+            year: Some(course.year),
+            lab: None,
+            matched_courses: None,
+            result: None,
+        }
+    }
+}
+
+/// Computes the min or max of a field across the given courses
+fn compute_function(
+    name: &qualification::FunctionNameEnum,
+    prop: &FieldNameEnum,
+    courses: &[Course],
+) -> Option<serde_json::Value> {
+    let mut numbers: Vec<i32> = vec![];
+    let mut decimals: Vec<ordered_float::OrderedFloat<f32>> = vec![];
+    let mut booleans: Vec<bool> = vec![];
+    let mut strings: Vec<&String> = vec![];
+
+    for course in courses {
+        match field_value(course, prop) {
+            FieldValue::Number(n) => numbers.push(n),
+            FieldValue::Decimal(n) => decimals.push(ordered_float::OrderedFloat(n)),
+            FieldValue::Boolean(b) => booleans.push(b),
+            FieldValue::List(items) => strings.extend(items),
+        }
+    }
+
+    match name {
+        qualification::FunctionNameEnum::Max => numbers
+            .into_iter()
+            .max()
+            .map(serde_json::Value::from)
+            .or_else(|| decimals.into_iter().max().map(|n| n.into_inner().into()))
+            .or_else(|| booleans.into_iter().max().map(serde_json::Value::from))
+            .or_else(|| {
+                strings
+                    .into_iter()
+                    .max()
+                    .cloned()
+                    .map(serde_json::Value::from)
+            }),
+        qualification::FunctionNameEnum::Min => numbers
+            .into_iter()
+            .min()
+            .map(serde_json::Value::from)
+            .or_else(|| decimals.into_iter().min().map(|n| n.into_inner().into()))
+            .or_else(|| booleans.into_iter().min().map(serde_json::Value::from))
+            .or_else(|| {
+                strings
+                    .into_iter()
+                    .min()
+                    .cloned()
+                    .map(serde_json::Value::from)
+            }),
     }
 }
 
 fn filter_by_qualification(
     filtered: Vec<Course>,
-    clause: qualification::SingleQualification,
+    clause: &mut qualification::SingleQualification,
     distinct: bool,
     all_courses: Option<Vec<Course>>,
     counter: Option<counter::ExpressionCounter>,
 ) -> Vec<Course> {
     let mut filtered = filtered.clone();
 
-    let _computed_value: Option<i32> = None;
+    if let QualificationValue::Function(func) = &mut clause.value {
+        let values = all_courses.unwrap_or_else(|| filtered.clone());
+        let matches = filter_by_where_clause(
+            values.clone(),
+            &mut func.qualifier,
+            false,
+            Some(values),
+            None,
+        );
 
-    if let qualification::QualificationValue::Function(func) = clause.clone().value {
-        let _values = all_courses
-            .unwrap_or(filtered.clone())
-            .into_iter()
-            /*.filter(filter_by_where_clause)*/
-            .map(|c| c);
-        match func.name {
-            qualification::FunctionNameEnum::Max => {}
-            qualification::FunctionNameEnum::Min => {}
-        };
+        // kept on the qualification, so that the result shows what was compared against
+        func.computed_value = compute_function(&func.name, &func.prop, &matches);
     }
 
-    filtered = filtered.into_iter().filter(|c| *c == clause).collect();
+    filtered.retain(|c| c == &*clause);
 
     if let Some(counter) = counter {
         if let Some(num_to_take) = counter.num {
@@ -182,22 +381,25 @@ fn filter_by_qualification(
                 counter::Operator::Lte | counter::Operator::Eq => {
                     filtered = filtered.into_iter().take(num_to_take as usize).collect()
                 }
-                counter::Operator::Gte => filtered = filtered,
+                counter::Operator::Gte => {}
             }
         }
     }
 
     if distinct {
-        filtered.sort();
-        filtered.dedup();
+        let mut seen = HashSet::new();
+        filtered.retain(|c| seen.insert(c.crsid.clone()));
     }
 
+    clause.matched_courses = Some(filtered.clone());
+    clause.result = Some(!filtered.is_empty());
+
     filtered
 }
 
-fn filter_by_where_clause(
+pub fn filter_by_where_clause(
     filtered: Vec<Course>,
-    clause: qualification::Qualification,
+    clause: &mut qualification::Qualification,
     distinct: bool,
     all_courses: Option<Vec<Course>>,
     counter: Option<counter::ExpressionCounter>,
@@ -208,7 +410,7 @@ fn filter_by_where_clause(
         }
         qualification::Qualification::BooleanAnd(clause) => {
             let mut filtered = filtered;
-            for q in clause.values {
+            for q in clause.values.iter_mut() {
                 filtered = filter_by_where_clause(
                     filtered,
                     q,
@@ -217,12 +419,15 @@ fn filter_by_where_clause(
                     counter.clone(),
                 );
             }
+
+            clause.matched_courses = Some(filtered.clone());
+            clause.result = Some(!filtered.is_empty());
             filtered
         }
         qualification::Qualification::BooleanOr(clause) => {
-            let mut filtered = filtered;
-            for q in clause.values {
-                filtered.extend(filter_by_where_clause(
+            let mut matched = vec![];
+            for q in clause.values.iter_mut() {
+                matched.extend(filter_by_where_clause(
                     filtered.clone(),
                     q,
                     distinct,
@@ -230,61 +435,167 @@ fn filter_by_where_clause(
                     counter.clone(),
                 ));
             }
-            filtered.sort();
-            filtered.dedup();
-            filtered
+            matched.sort();
+            matched.dedup();
+
+            clause.matched_courses = Some(matched.clone());
+            clause.result = Some(!matched.is_empty());
+            matched
         }
     }
 }
 
-fn apply_filter(filter: FilterExpression, courses: CourseList) -> CourseList {
+pub(crate) fn apply_filter(filter: &mut FilterExpression, courses: CourseList) -> CourseList {
     match filter {
         FilterExpression::Of(expr) => courses
             .into_iter()
             .filter(|c| expr.of.iter().any(|e| e == c))
             .collect(),
         FilterExpression::Where(expr) => {
-            filter_by_where_clause(courses, expr.qualification, false, None, None)
+            filter_by_where_clause(courses, &mut expr.qualification, expr.distinct, None, None)
         }
     }
 }
 
-fn make_requirement_path(path: &Vec<&str>) -> String {
-    path.join("\x1C").to_lowercase()
+/// Replaces the first course expression that none of `courses` satisfy with the fulfillment,
+/// where `courses` are the ones the requirement is still free to use.
+///
+/// Only plain courses, and the courses inside `and`, `or` and `of`, are replaced; `where`
+/// clauses, modifiers and occurrences are left as written. The fulfillment is still added to
+/// the courses that those see, so it counts towards them wherever it qualifies.
+///
+/// Returns whether anything was replaced.
+fn substitute_fulfillment(
+    expr: &mut HansonExpression,
+    fulfillment: &Course,
+    courses: &[Course],
+) -> bool {
+    match expr {
+        HansonExpression::Course(course) => {
+            if courses.iter().any(|c| c == &*course) {
+                return false;
+            }
+
+            *course = course::CourseExpression::from(fulfillment);
+            true
+        }
+        HansonExpression::BooleanAnd(and) => and
+            .values
+            .iter_mut()
+            .any(|v| substitute_fulfillment(v, fulfillment, courses)),
+        HansonExpression::BooleanOr(or) => or
+            .values
+            .iter_mut()
+            .any(|v| substitute_fulfillment(v, fulfillment, courses)),
+        HansonExpression::Of(of) => of
+            .of
+            .iter_mut()
+            .any(|v| substitute_fulfillment(v, fulfillment, courses)),
+        _ => false,
+    }
 }
 
 fn apply_fulfillment_to_expression(
-    result_expr: HansonExpression,
-    _fulfillment_value: Course,
+    mut result_expr: HansonExpression,
+    fulfillment_value: Course,
+    courses: &[Course],
 ) -> HansonExpression {
+    substitute_fulfillment(&mut result_expr, &fulfillment_value, courses);
     result_expr
 }
 
+/// The student's overrides and fulfillments, which each requirement looks up by its path
+#[derive(Clone, Copy)]
+struct Adjustments<'a> {
+    overrides: &'a OverrideMap,
+    fulfillments: &'a FulfillmentMap,
+}
+
+/// Evaluates a list of sibling requirements, each after the siblings that it refers to, and
+/// returns them in the order they were declared
+fn compute_siblings(
+    requirements: &[Requirement],
+    path: &RequirementPath,
+    courses: &[Course],
+    dirty: &[Course],
+    share_courses: bool,
+    claims: &mut ClaimTracker,
+    adjustments: Adjustments,
+) -> Vec<Requirement> {
+    // a cycle has already been reported as the area's error, so the order hardly matters
+    let order = dependency::evaluation_order(requirements)
+        .unwrap_or_else(|_| (0..requirements.len()).collect());
+
+    let mut evaluated: Vec<Option<Requirement>> = vec![None; requirements.len()];
+    for index in order {
+        // unless they've been told to share, a requirement can't use what the siblings that
+        // were evaluated before it claimed
+        let mut sibling_dirty = dirty.to_vec();
+        if !share_courses {
+            sibling_dirty.extend(claims.claimed_within(path));
+        }
+
+        let siblings: Vec<Requirement> = evaluated.iter().flatten().cloned().collect();
+
+        evaluated[index] = Some(compute_requirement(
+            requirements[index].clone(),
+            path,
+            courses.to_vec(),
+            &sibling_dirty,
+            &siblings,
+            claims,
+            adjustments,
+        ));
+    }
+
+    evaluated.into_iter().flatten().collect()
+}
+
+/// The courses matched by the siblings that an expression refers to, which the sibling has
+/// already claimed for itself
+fn courses_from_siblings(
+    expr: &HansonExpression,
+    children: &[Requirement],
+    siblings: &[Requirement],
+) -> Vec<Course> {
+    let mut references = vec![];
+    collect_references(expr, &mut references);
+
+    references
+        .into_iter()
+        .filter(|name| !children.iter().any(|c| &c.name == name))
+        .filter_map(|name| siblings.iter().find(|s| s.name == name))
+        .filter_map(|sibling| sibling.evaluated.as_ref())
+        .flat_map(|detail| detail.matched_courses.clone())
+        .collect()
+}
+
 fn compute_requirement(
     requirement: Requirement,
-    path: Vec<&str>,
+    path: &RequirementPath,
     mut courses: CourseList,
-    overrides: OverrideMap,
-    fulfillments: FulfillmentMap,
+    dirty: &[Course],
+    siblings: &[Requirement],
+    claims: &mut ClaimTracker,
+    adjustments: Adjustments,
 ) -> Requirement {
-    let req_name = requirement.name.clone();
-
-    let mut path_to_here: Vec<&str> = vec![];
-    path_to_here.extend(path.iter().cloned());
-    path_to_here.push(&req_name);
-
-    let children_results = requirement
-        .children
-        .iter()
-        .map(|req| {
-            compute_requirement(
-                req.clone(),
-                path.clone(),
-                courses.clone(),
-                overrides.clone(),
-                fulfillments.clone(),
-            )
-        }).collect();
+    let path_to_here = path.child(&requirement.name);
+    let children_share_courses = requirement.children_share_courses.unwrap_or(false);
+
+    let children_results = compute_siblings(
+        &requirement.children,
+        &path_to_here,
+        &courses,
+        dirty,
+        children_share_courses,
+        claims,
+        adjustments,
+    );
+
+    // references resolve to the evaluated children first, and then to the siblings that have
+    // already been evaluated
+    let mut in_scope = children_results.clone();
+    in_scope.extend_from_slice(siblings);
 
     if let Some(mut result_expr) = requirement.result.clone() {
         let mut applied_fulfillment: Option<Course> = None;
@@ -292,48 +603,107 @@ fn compute_requirement(
         let mut was_overridden = false;
         let computed_result;
 
-        if let Some(filter) = requirement.filter.clone() {
+        let mut filter = requirement.filter.clone();
+        if let Some(filter) = &mut filter {
             courses = apply_filter(filter, courses.clone());
         }
 
-        let fulfillment = fulfillments.get(&make_requirement_path(&path));
+        // the requirement can reach its children's courses through references, but it can't
+        // claim them a second time on its own
+        let mut own_dirty = dirty.to_vec();
+        own_dirty.extend(claims.claimed_within(&path_to_here));
+        own_dirty.extend(claims.reserved_elsewhere(&path_to_here));
+
+        let fulfillment = adjustments.fulfillments.get(&path_to_here);
         if let Some(value) = fulfillment {
             applied_fulfillment = Some(value.clone());
-            result_expr = apply_fulfillment_to_expression(result_expr.clone(), value.clone());
+
+            // a course that a sibling has claimed is as missing as one never taken
+            let available: Vec<Course> = courses
+                .iter()
+                .filter(|c| !own_dirty.contains(c))
+                .cloned()
+                .collect();
+            result_expr =
+                apply_fulfillment_to_expression(result_expr.clone(), value.clone(), &available);
 
             computed_result = compute_expression(
-                result_expr,
-                &requirement.children,
-                courses,
-                vec![],
+                &mut result_expr,
+                &in_scope,
+                courses.clone(),
+                own_dirty,
                 Some(value.clone()),
             );
         } else {
-            computed_result =
-                compute_expression(result_expr, &requirement.children, courses, vec![], None);
+            computed_result = compute_expression(
+                &mut result_expr,
+                &in_scope,
+                courses.clone(),
+                own_dirty,
+                None,
+            );
         }
 
         let mut success = computed_result.success;
         let matched_courses = computed_result.clone().matched_courses;
+        let mut progress = computed_result.progress;
+
+        // the courses that came from a sibling are still that sibling's
+        let from_siblings = courses_from_siblings(&result_expr, &children_results, siblings);
+        let claimable: Vec<Course> = matched_courses
+            .iter()
+            .filter(|c| !from_siblings.contains(c))
+            .cloned()
+            .collect();
+        let claimed_courses = claims.claim(&path_to_here, &claimable);
+
+        // when the requirement fails, find out which of the courses it could have used were
+        // already claimed elsewhere
+        let mut unavailable_courses = vec![];
+        if !success && !dirty.is_empty() {
+            let unrestricted = compute_expression(
+                &mut result_expr.clone(),
+                &in_scope,
+                courses,
+                vec![],
+                applied_fulfillment.clone(),
+            );
+
+            unavailable_courses = unrestricted
+                .matched_courses
+                .iter()
+                .filter(|c| !matched_courses.contains(c))
+                .filter_map(|c| claims.claim_outside(&path_to_here, c))
+                .cloned()
+                .collect();
+        }
 
-        let req_override = overrides.get(&make_requirement_path(&path));
+        let req_override = adjustments.overrides.get(&path_to_here);
         if let Some(value) = req_override {
             was_overridden = true;
             success = *value;
+
+            if success {
+                progress = progress.completed();
+            }
         }
 
         return Requirement {
             // ..requirement,
             children_share_courses: requirement.children_share_courses,
-            filter: requirement.filter,
+            filter,
             message: requirement.message,
             name: requirement.name,
-            result: requirement.result,
+            result: Some(result_expr),
             evaluated: Some(RequirementEvaluation {
                 applied_fulfillment,
+                credits_matched: distinct_credits(&matched_courses),
                 matched_courses,
+                claimed_courses,
+                unavailable_courses,
                 success,
                 overridden: was_overridden,
+                progress,
             }),
             children: children_results,
         };
@@ -350,69 +720,445 @@ fn compute_requirement(
     }
 }
 
-fn compute_progress(results: &[Requirement]) -> (usize, usize) {
-    let successes: Vec<bool> = results
-        .iter()
-        .map(|r| match &r.evaluated {
-            Some(evaluated) => evaluated.success,
-            None => false,
-        }).filter(|&pass| pass == true)
-        .collect();
+/// Lists the names of the requirements that an expression refers to
+pub(crate) fn collect_references<'a>(expr: &'a HansonExpression, found: &mut Vec<&'a str>) {
+    match expr {
+        HansonExpression::Reference(reference) => found.push(&reference.requirement),
+        HansonExpression::BooleanAnd(and) => {
+            and.values.iter().for_each(|v| collect_references(v, found))
+        }
+        HansonExpression::BooleanOr(or) => {
+            or.values.iter().for_each(|v| collect_references(v, found))
+        }
+        HansonExpression::Of(of) => of.of.iter().for_each(|v| collect_references(v, found)),
+        HansonExpression::Modifier(ModifierExpression::Children(modifier)) => {
+            found.extend(modifier.children.iter().map(|r| r.requirement.as_str()))
+        }
+        HansonExpression::Modifier(ModifierExpression::ChildrenWhere(modifier)) => {
+            found.extend(modifier.children.iter().map(|r| r.requirement.as_str()))
+        }
+        _ => {}
+    }
+}
+
+fn find_unknown_references(
+    result: Option<&HansonExpression>,
+    children: &[Requirement],
+    siblings: &[Requirement],
+    path: &RequirementPath,
+    errors: &mut Vec<Error>,
+) {
+    let mut references = vec![];
+    if let Some(result) = result {
+        collect_references(result, &mut references);
+    }
+
+    for reference in references {
+        let is_known = children.iter().chain(siblings).any(|r| r.name == reference);
 
-    (successes.len(), results.len())
+        if !is_known {
+            errors.push(Error::UnknownReference {
+                requirement: path.clone(),
+                reference: reference.to_string(),
+            });
+        }
+    }
+
+    for child in children {
+        find_unknown_references(
+            child.result.as_ref(),
+            &child.children,
+            children,
+            &path.child(&child.name),
+            errors,
+        );
+    }
+}
+
+/// Finds every reference in the area that names neither a child nor a sibling of the
+/// requirement making it
+pub fn unknown_references(area_of_study: &AreaOfStudy) -> Vec<Error> {
+    let mut errors = vec![];
+    find_unknown_references(
+        Some(&area_of_study.result),
+        &area_of_study.children,
+        &[],
+        &RequirementPath::area(&area_of_study.area_name, &area_of_study.area_type),
+        &mut errors,
+    );
+    errors
 }
 
+/// Evaluates an area, keeping each of the `reservations` away from every requirement but its
+/// claimant
 pub fn evaluate_area(
     courses: &[Course],
     overrides: &OverrideMap,
     fulfillments: &FulfillmentMap,
     area_of_study: AreaOfStudy,
+    reservations: &[Claim],
 ) -> AreaOfStudy {
     // 1. Recursively call compute_requirement() on all children
     // 2. Compute this result
 
-    let name = area_of_study.area_name.clone();
-    let kind = area_of_study.area_type.clone();
-    let path: Vec<&str> = vec![&name, &kind];
-
-    let results: Vec<Requirement> = area_of_study
-        .children
-        .iter()
-        .map(|req| {
-            compute_requirement(
-                req.clone(),
-                path.clone(),
-                courses.to_vec(),
-                overrides.clone(),
-                fulfillments.clone(),
-            )
-        }).collect();
-
-    // let matched_courses = results.iter().map(|res| res.matched_courses).
+    let path = RequirementPath::area(&area_of_study.area_name, &area_of_study.area_type);
 
-    let result = compute_expression(
-        area_of_study.result.clone(),
+    // an area that refers to requirements which don't exist, or whose requirements refer to
+    // each other in a loop, can't be trusted to pass
+    let error = unknown_references(&area_of_study)
+        .into_iter()
+        .chain(dependency::reference_cycles(&area_of_study))
+        .next()
+        .map(|err| err.to_string());
+
+    // the top-level requirements of an area never share courses with one another
+    let mut claims = ClaimTracker::with_reservations(reservations);
+    let results = compute_siblings(
         &area_of_study.children,
+        &path,
+        courses,
+        &[],
+        false,
+        &mut claims,
+        Adjustments {
+            overrides,
+            fulfillments,
+        },
+    );
+
+    let mut result_expr = area_of_study.result.clone();
+    let result = compute_expression(
+        &mut result_expr,
+        &results,
         courses.to_vec(),
-        vec![],
+        claims.claimed_within(&path),
         None,
     );
 
     let computed_result = result.success;
 
-    let progress = compute_progress(&results);
+    let progress = result.progress;
 
     AreaOfStudy {
         area_name: area_of_study.area_name,
         area_revision: area_of_study.area_revision,
         area_type: area_of_study.area_type,
         area_url: area_of_study.area_url,
-        result: area_of_study.result,
+        result: result_expr,
         children: results,
         evaluated: Some(AreaOfStudyEvaluation {
-            success: computed_result,
-            error: None,
+            success: computed_result && error.is_none(),
+            error,
             progress,
+            credits_matched: distinct_credits(&result.matched_courses),
+            alternatives: vec![],
         }),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::course::course;
+    use crate::hanson::parse_area;
+
+    #[test]
+    fn fulfillments_stand_in_for_missing_courses() {
+        let area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: Intro
+
+Intro: CSCI 121 & CSCI 125
+",
+        )
+        .unwrap();
+
+        let mut fulfillments = FulfillmentMap::new();
+        fulfillments.insert("Example/major/Intro".parse().unwrap(), course("CSCI", 126));
+
+        let result = evaluate_area(
+            &[course("CSCI", 121)],
+            &OverrideMap::new(),
+            &fulfillments,
+            area,
+            &[],
+        );
+
+        let intro = &result.children[0];
+        let detail = intro.evaluated.as_ref().unwrap();
+        assert!(detail.success);
+        assert_eq!(detail.applied_fulfillment, Some(course("CSCI", 126)));
+        assert_eq!(detail.matched_courses.len(), 2);
+
+        match &intro.result {
+            Some(HansonExpression::BooleanAnd(and)) => match &and.values[1] {
+                HansonExpression::Course(c) => assert_eq!(c.number, 126),
+                other => panic!("expected a course, got {:?}", other),
+            },
+            other => panic!("expected an and, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn fulfillments_stand_in_for_claimed_courses() {
+        let area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: Intro & Core
+
+Intro: CSCI 121
+Core: CSCI 121 & CSCI 125
+",
+        )
+        .unwrap();
+
+        let mut fulfillments = FulfillmentMap::new();
+        fulfillments.insert("Example/major/Core".parse().unwrap(), course("CSCI", 126));
+
+        let result = evaluate_area(
+            &[course("CSCI", 121), course("CSCI", 125)],
+            &OverrideMap::new(),
+            &fulfillments,
+            area,
+            &[],
+        );
+
+        assert!(result.evaluated.unwrap().success);
+
+        let core = &result.children[1];
+        let matched = &core.evaluated.as_ref().unwrap().matched_courses;
+        assert!(matched.contains(&course("CSCI", 126)));
+        assert!(!matched.contains(&course("CSCI", 121)));
+    }
+
+    #[test]
+    fn credit_requirements_add_partial_credits_exactly() {
+        let area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: Upper Level
+
+Upper Level: at least two credits where { level >= 200 }
+",
+        )
+        .unwrap();
+
+        let with_credits = |number, credits| {
+            let mut c = course("CSCI", number);
+            c.credits = ordered_float::OrderedFloat(credits);
+            c
+        };
+
+        let courses = vec![
+            with_credits(121, 1.0),
+            with_credits(251, 1.0),
+            with_credits(300, 0.1),
+            with_credits(301, 0.1),
+            with_credits(302, 0.1),
+            with_credits(390, 0.7),
+        ];
+
+        let result = evaluate_area(
+            &courses,
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area.clone(),
+            &[],
+        );
+
+        let detail = result.children[0].evaluated.as_ref().unwrap();
+        assert!(detail.success);
+        assert_eq!(detail.credits_matched, Credits::whole(2));
+        assert_eq!(result.evaluated.unwrap().credits_matched, Credits::whole(2));
+
+        let result = evaluate_area(
+            &courses[..5],
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area,
+            &[],
+        );
+
+        let detail = result.children[0].evaluated.as_ref().unwrap();
+        assert!(!detail.success);
+        assert_eq!(detail.credits_matched.to_string(), "1.3");
+        assert_eq!(
+            detail.progress.credits,
+            (Credits::from(1.3), Credits::whole(2))
+        );
+
+        let area_progress = result.evaluated.unwrap().progress;
+        assert_eq!(area_progress.requirements, (0, 1));
+        assert_eq!(area_progress.fraction, 1.3 / 3.0);
+    }
+
+    #[test]
+    fn references_see_evaluated_requirements() {
+        let area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: Core & Summary
+
+Summary: Core
+Core:
+  result: Intro & Theory
+  Intro: CSCI 121
+  Theory: CSCI 251
+",
+        )
+        .unwrap();
+
+        let result = evaluate_area(
+            &[course("CSCI", 121), course("CSCI", 251)],
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area,
+            &[],
+        );
+
+        assert!(result.evaluated.unwrap().success);
+
+        // the summary comes first, but it's evaluated after the sibling that it refers to
+        let summary = result.children[0].evaluated.as_ref().unwrap();
+        assert_eq!(result.children[0].name, "Summary");
+        assert!(summary.success);
+        assert_eq!(summary.matched_courses.len(), 2);
+        assert!(summary.claimed_courses.is_empty());
+
+        let core = result.children[1].evaluated.as_ref().unwrap();
+        assert!(core.success);
+        assert!(core.claimed_courses.is_empty());
+    }
+
+    #[test]
+    fn reference_cycles_are_reported() {
+        let area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: A & B
+
+A: B
+B: A
+",
+        )
+        .unwrap();
+
+        let result = evaluate_area(&[], &OverrideMap::new(), &FulfillmentMap::new(), area, &[]);
+        let detail = result.evaluated.unwrap();
+
+        assert!(!detail.success);
+        assert_eq!(
+            detail.error.unwrap(),
+            "requirements refer to each other in a cycle: `Example/major/A` -> `Example/major/B` \
+             -> `Example/major/A`"
+        );
+    }
+
+    #[test]
+    fn expressions_record_how_they_were_satisfied() {
+        let area = parse_area(
+            "
+name: Example
+type: major
+revision: 2018-19
+result: Intro
+
+Intro: CSCI 121 & (CSCI 125 | CSCI 126) & one course where { level >= 300 }
+",
+        )
+        .unwrap();
+
+        let result = evaluate_area(
+            &[course("CSCI", 121), course("CSCI", 126)],
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area,
+            &[],
+        );
+
+        let and = match &result.children[0].result {
+            Some(HansonExpression::BooleanAnd(and)) => and,
+            other => panic!("expected an and, got {:?}", other),
+        };
+        assert_eq!(and.result, Some(false));
+
+        match &and.values[0] {
+            HansonExpression::Course(c) => {
+                assert_eq!(c.result, Some(true));
+                assert_eq!(c.matched_courses, Some(vec![course("CSCI", 121)]));
+            }
+            other => panic!("expected a course, got {:?}", other),
+        }
+
+        match &and.values[1] {
+            HansonExpression::BooleanOr(or) => {
+                assert_eq!(or.result, Some(true));
+                assert_eq!(or.matched_courses, Some(vec![course("CSCI", 126)]));
+
+                match &or.values[0] {
+                    HansonExpression::Course(c) => assert_eq!(c.result, Some(false)),
+                    other => panic!("expected a course, got {:?}", other),
+                }
+            }
+            other => panic!("expected an or, got {:?}", other),
+        }
+
+        match &and.values[2] {
+            HansonExpression::Where(expr) => {
+                assert_eq!(expr.result, Some(false));
+
+                match &expr.qualification {
+                    qualification::Qualification::Single(q) => {
+                        assert_eq!(q.result, Some(false));
+                        assert_eq!(q.matched_courses, Some(vec![]));
+                    }
+                    other => panic!("expected a qualification, got {:?}", other),
+                }
+            }
+            other => panic!("expected a where-expression, got {:?}", other),
+        }
+
+        match &result.result {
+            HansonExpression::Reference(r) => assert_eq!(r.result, Some(false)),
+            other => panic!("expected a reference, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_references_are_reported() {
+        let area = crate::parse::parse_area(
+            r#"{
+                "name": "Example", "type": "major", "revision": "2018-19", "slug": null,
+                "result": {"type": "Reference", "requirement": "Intro"},
+                "children": [{
+                    "name": "Intro",
+                    "result": {"type": "Reference", "requirement": "Missing"},
+                    "children": []
+                }]
+            }"#
+            .to_string(),
+        )
+        .unwrap();
+
+        let result = evaluate_area(&[], &OverrideMap::new(), &FulfillmentMap::new(), area, &[]);
+        let detail = result.evaluated.unwrap();
+
+        assert!(!detail.success);
+        assert_eq!(
+            detail.error.unwrap(),
+            "`Example/major/Intro` refers to `Missing`, \
+             which is neither one of its children nor one of its siblings"
+        );
+    }
+}
@@ -1,4 +1,6 @@
+use crate::course::Credits;
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Operator {
@@ -20,3 +22,116 @@ pub struct ExpressionCounter {
     pub was: Option<Shorthand>,
     pub num: Option<u32>,
 }
+
+impl ExpressionCounter {
+    /// The number of items needed to satisfy this counter, out of `available` options
+    pub fn required(&self, available: usize) -> usize {
+        match (&self.was, self.num) {
+            (Some(Shorthand::All), _) => available,
+            (Some(Shorthand::Any), _) => 1,
+            (Some(Shorthand::None), _) => 0,
+            (None, Some(num)) => num as usize,
+            (None, None) => available,
+        }
+    }
+
+    /// Checks whether having `count` of the `available` options satisfies this counter
+    pub fn is_satisfied(&self, count: usize, available: usize) -> bool {
+        let required = self.required(available);
+
+        match self.operator {
+            Operator::Eq => count == required,
+            Operator::Gte => count >= required,
+            Operator::Lte => count <= required,
+        }
+    }
+
+    /// Checks whether a total of `credits` satisfies this counter, reading `num` as credits
+    pub fn is_satisfied_by_credits(&self, credits: Credits) -> bool {
+        let required = Credits::whole(self.num.unwrap_or(0));
+
+        match self.operator {
+            Operator::Eq => credits == required,
+            Operator::Gte => credits >= required,
+            Operator::Lte => credits <= required,
+        }
+    }
+
+    /// Describes how far `count` of the `available` options goes towards this counter, as a
+    /// `(have, need)` pair.
+    ///
+    /// When there are more than an "at most" or "exactly" counter allows, it's as far along as
+    /// the share of them that could be kept.
+    pub fn progress(&self, count: usize, available: usize) -> (usize, usize) {
+        let required = self.required(available);
+
+        match self.operator {
+            Operator::Lte | Operator::Eq if count > required => (required, count),
+            Operator::Lte => (required, required),
+            Operator::Eq | Operator::Gte => (count.min(required), required),
+        }
+    }
+}
+
+impl fmt::Display for ExpressionCounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.was, self.num) {
+            (Some(Shorthand::All), _) => write!(f, "all"),
+            (Some(Shorthand::Any), _) => write!(f, "any"),
+            (Some(Shorthand::None), _) => write!(f, "none"),
+            (None, num) => {
+                let num = num.map_or("all".to_string(), |n| n.to_string());
+                match self.operator {
+                    Operator::Eq => write!(f, "exactly {}", num),
+                    Operator::Gte => write!(f, "at least {}", num),
+                    Operator::Lte => write!(f, "at most {}", num),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn counter(operator: Operator, was: Option<Shorthand>, num: Option<u32>) -> ExpressionCounter {
+        ExpressionCounter { operator, was, num }
+    }
+
+    #[test]
+    fn shorthands() {
+        let all = counter(Operator::Eq, Some(Shorthand::All), None);
+        assert!(all.is_satisfied(4, 4));
+        assert!(!all.is_satisfied(3, 4));
+        assert_eq!(all.progress(3, 4), (3, 4));
+
+        let any = counter(Operator::Gte, Some(Shorthand::Any), Some(1));
+        assert!(any.is_satisfied(2, 4));
+        assert!(!any.is_satisfied(0, 4));
+
+        let none = counter(Operator::Eq, Some(Shorthand::None), Some(0));
+        assert!(none.is_satisfied(0, 4));
+        assert!(!none.is_satisfied(1, 4));
+        assert_eq!(none.progress(1, 4), (0, 1));
+    }
+
+    #[test]
+    fn operators() {
+        let at_least_two = counter(Operator::Gte, None, Some(2));
+        assert!(at_least_two.is_satisfied(3, 9));
+        assert!(!at_least_two.is_satisfied(1, 9));
+        assert_eq!(at_least_two.progress(3, 9), (2, 2));
+
+        let at_most_two = counter(Operator::Lte, None, Some(2));
+        assert!(at_most_two.is_satisfied(2, 9));
+        assert!(!at_most_two.is_satisfied(3, 9));
+        assert_eq!(at_most_two.progress(1, 9), (2, 2));
+        assert_eq!(at_most_two.progress(4, 9), (2, 4));
+
+        let exactly_two = counter(Operator::Eq, None, Some(2));
+        assert!(exactly_two.is_satisfied(2, 9));
+        assert!(!exactly_two.is_satisfied(3, 9));
+        assert_eq!(exactly_two.progress(3, 9), (2, 3));
+    }
+}
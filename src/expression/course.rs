@@ -1,4 +1,5 @@
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct CourseExpression {
@@ -7,6 +8,32 @@ pub struct CourseExpression {
     pub level: Option<i32>,
     pub semester: Option<i32>,
     pub year: Option<i32>,
+    pub lab: Option<bool>,
+
+    pub matched_courses: Option<Vec<crate::evaluate::Course>>,
+    pub result: Option<bool>,
+}
+
+/// Writes the expression the way it's written in Hanson, as in `CSCI 121`, `PHYS 130L` or
+/// `MATH 282.2014.1`
+impl fmt::Display for CourseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.department.join("/"), self.number)?;
+
+        if self.lab == Some(true) {
+            write!(f, "L")?;
+        }
+
+        if let Some(year) = self.year {
+            write!(f, ".{}", year)?;
+
+            if let Some(semester) = self.semester {
+                write!(f, ".{}", semester)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -16,11 +43,12 @@ mod test {
 
     #[test]
     fn courses_vs_course_exprs() {
-        let CourseExpression = FullCourse {
+        let course = FullCourse {
             clbid: "1".to_string(),
             credits: ordered_float::OrderedFloat(1.0),
             crsid: "1".to_string(),
             department: vec!["CSCI".to_string()],
+            gereqs: vec![],
             groupid: Some("1".to_string()),
             grouptype: Some("R".to_string()),
             level: 100,
@@ -28,6 +56,7 @@ mod test {
             section: Some("A".to_string()),
             semester: 1,
             year: 2000,
+            ..FullCourse::default()
         };
 
         let yes_expr = CourseExpression {
@@ -36,18 +65,22 @@ mod test {
             number: 101,
             semester: None,
             year: None,
+            lab: None,
+            matched_courses: None,
+            result: None,
         };
 
-        assert_eq!(yes_expr, CourseExpression);
+        assert_eq!(yes_expr, course);
     }
 
     #[test]
     fn courses_vs_course_exprs_diff_depts() {
-        let CourseExpression = FullCourse {
+        let course = FullCourse {
             clbid: "1".to_string(),
             credits: ordered_float::OrderedFloat(1.0),
             crsid: "1".to_string(),
             department: vec!["CSCI".to_string()],
+            gereqs: vec![],
             groupid: Some("1".to_string()),
             grouptype: Some("R".to_string()),
             level: 100,
@@ -55,6 +88,7 @@ mod test {
             section: Some("A".to_string()),
             semester: 1,
             year: 2000,
+            ..FullCourse::default()
         };
 
         let no_expr = CourseExpression {
@@ -63,8 +97,37 @@ mod test {
             number: 101,
             semester: None,
             year: None,
+            lab: None,
+            matched_courses: None,
+            result: None,
+        };
+
+        assert_ne!(no_expr, course);
+    }
+
+    #[test]
+    fn labs_only_match_lab_sections() {
+        let mut course = FullCourse {
+            department: vec!["PHYS".to_string()],
+            level: 100,
+            number: 130,
+            ..FullCourse::default()
+        };
+
+        let lab_expr = CourseExpression {
+            department: vec!["PHYS".to_string()],
+            level: None,
+            number: 130,
+            semester: None,
+            year: None,
+            lab: Some(true),
+            matched_courses: None,
+            result: None,
         };
 
-        assert_ne!(no_expr, CourseExpression);
+        assert_ne!(lab_expr, course);
+
+        course.course_type = Some("Lab".to_string());
+        assert_eq!(lab_expr, course);
     }
 }
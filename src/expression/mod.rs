@@ -4,7 +4,7 @@ mod boolean;
 pub mod counter;
 pub mod course;
 pub mod filter;
-mod modifier;
+pub mod modifier;
 mod occurrence;
 mod of;
 pub mod qualification;
@@ -20,7 +20,7 @@ pub use self::reference::ReferenceExpression;
 pub use self::where_expr::WhereExpression;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "type", from = "TaggedExpression")]
 pub enum HansonExpression {
     BooleanAnd(BooleanAndExpression),
     BooleanOr(BooleanOrExpression),
@@ -29,5 +29,41 @@ pub enum HansonExpression {
     Occurrence(OccurrenceExpression),
     Of(OfExpression),
     Reference(ReferenceExpression),
+    #[serde(alias = "where")]
+    Where(WhereExpression),
+}
+
+/// An expression as it's tagged in expanded JSON, where a lab is an expression of its own
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum TaggedExpression {
+    BooleanAnd(BooleanAndExpression),
+    BooleanOr(BooleanOrExpression),
+    Course(CourseExpression),
+    Lab(CourseExpression),
+    Modifier(ModifierExpression),
+    Occurrence(OccurrenceExpression),
+    Of(OfExpression),
+    Reference(ReferenceExpression),
+    #[serde(alias = "where")]
     Where(WhereExpression),
 }
+
+impl From<TaggedExpression> for HansonExpression {
+    fn from(tagged: TaggedExpression) -> HansonExpression {
+        match tagged {
+            TaggedExpression::BooleanAnd(expr) => HansonExpression::BooleanAnd(expr),
+            TaggedExpression::BooleanOr(expr) => HansonExpression::BooleanOr(expr),
+            TaggedExpression::Course(expr) => HansonExpression::Course(expr),
+            TaggedExpression::Lab(expr) => HansonExpression::Course(CourseExpression {
+                lab: Some(true),
+                ..expr
+            }),
+            TaggedExpression::Modifier(expr) => HansonExpression::Modifier(expr),
+            TaggedExpression::Occurrence(expr) => HansonExpression::Occurrence(expr),
+            TaggedExpression::Of(expr) => HansonExpression::Of(expr),
+            TaggedExpression::Reference(expr) => HansonExpression::Reference(expr),
+            TaggedExpression::Where(expr) => HansonExpression::Where(expr),
+        }
+    }
+}
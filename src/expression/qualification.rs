@@ -1,5 +1,8 @@
 use serde_derive::{Deserialize, Serialize};
 use serde_json;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
 
 type StaticValue = serde_json::Value;
 
@@ -21,7 +24,8 @@ pub enum FunctionNameEnum {
     Min,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "String")]
 pub enum FieldNameEnum {
     #[serde(rename = "gereqs")]
     GeReq,
@@ -31,24 +35,117 @@ pub enum FieldNameEnum {
     Department,
     #[serde(rename = "level")]
     Level,
+    #[serde(rename = "number")]
+    Number,
+    #[serde(rename = "semester")]
+    Semester,
+    #[serde(rename = "credits")]
+    Credits,
+    #[serde(rename = "instructors")]
+    Instructors,
+    #[serde(rename = "type")]
+    Type,
+    #[serde(rename = "pn")]
+    Pn,
+    #[serde(rename = "term")]
+    Term,
+}
+
+impl FieldNameEnum {
+    pub const ALL: [FieldNameEnum; 11] = [
+        FieldNameEnum::GeReq,
+        FieldNameEnum::Year,
+        FieldNameEnum::Department,
+        FieldNameEnum::Level,
+        FieldNameEnum::Number,
+        FieldNameEnum::Semester,
+        FieldNameEnum::Credits,
+        FieldNameEnum::Instructors,
+        FieldNameEnum::Type,
+        FieldNameEnum::Pn,
+        FieldNameEnum::Term,
+    ];
+
+    /// The name of the field, as written in a qualification
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldNameEnum::GeReq => "gereqs",
+            FieldNameEnum::Year => "year",
+            FieldNameEnum::Department => "department",
+            FieldNameEnum::Level => "level",
+            FieldNameEnum::Number => "number",
+            FieldNameEnum::Semester => "semester",
+            FieldNameEnum::Credits => "credits",
+            FieldNameEnum::Instructors => "instructors",
+            FieldNameEnum::Type => "type",
+            FieldNameEnum::Pn => "pn",
+            FieldNameEnum::Term => "term",
+        }
+    }
+
+    /// Whether the field holds a list of names, like a course's departments, which a course can
+    /// be checked for but not ordered by
+    pub fn is_list(self) -> bool {
+        matches!(
+            self,
+            FieldNameEnum::GeReq
+                | FieldNameEnum::Department
+                | FieldNameEnum::Instructors
+                | FieldNameEnum::Type
+        )
+    }
+}
+
+impl fmt::Display for FieldNameEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for FieldNameEnum {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<FieldNameEnum, String> {
+        FieldNameEnum::ALL
+            .iter()
+            .find(|key| key.name() == input)
+            .cloned()
+            .ok_or_else(|| {
+                let known: Vec<&str> = FieldNameEnum::ALL.iter().map(|k| k.name()).collect();
+                format!(
+                    "unknown qualification key `{}`; expected one of {}",
+                    input,
+                    known.join(", ")
+                )
+            })
+    }
+}
+
+impl TryFrom<String> for FieldNameEnum {
+    type Error = String;
+
+    fn try_from(input: String) -> Result<FieldNameEnum, String> {
+        input.parse()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FunctionValue {
     pub name: FunctionNameEnum,
     pub prop: FieldNameEnum,
+    #[serde(alias = "qualification")]
     pub qualifier: Box<Qualification>,
     pub computed_value: Option<StaticValue>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NumericValue {
-    value: i32,
+    pub value: i32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StringValue {
-    value: String,
+    pub value: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -58,10 +155,11 @@ pub enum QualificationValue {
     String(StringValue),
     BooleanOr(BooleanOrValue),
     BooleanAnd(BooleanAndValue),
+    #[serde(alias = "function")]
     Function(FunctionValue),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum QualificationOperator {
     Lte,
     Lt,
@@ -71,6 +169,21 @@ pub enum QualificationOperator {
     Neq,
 }
 
+impl fmt::Display for QualificationOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator = match self {
+            QualificationOperator::Lte => "<=",
+            QualificationOperator::Lt => "<",
+            QualificationOperator::Eq => "=",
+            QualificationOperator::Gte => ">=",
+            QualificationOperator::Gt => ">",
+            QualificationOperator::Neq => "!=",
+        };
+
+        write!(f, "{}", operator)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrQualification {
     pub values: Vec<Qualification>,
@@ -105,3 +218,256 @@ pub enum Qualification {
     BooleanOr(OrQualification),
     BooleanAnd(AndQualification),
 }
+
+impl fmt::Display for QualificationValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |values: &[StaticValue], separator: &str| {
+            values
+                .iter()
+                .map(|v| match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    v => v.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+
+        match self {
+            QualificationValue::Number(n) => write!(f, "{}", n.value),
+            QualificationValue::String(s) => write!(f, "{}", s.value),
+            QualificationValue::BooleanOr(or) => write!(f, "({})", join(&or.values, " | ")),
+            QualificationValue::BooleanAnd(and) => write!(f, "({})", join(&and.values, " & ")),
+            QualificationValue::Function(function) => {
+                let name = match function.name {
+                    FunctionNameEnum::Max => "max",
+                    FunctionNameEnum::Min => "min",
+                };
+
+                write!(f, "{}({})", name, function.prop)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SingleQualification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.key, self.operator, self.value)
+    }
+}
+
+/// Writes out a whole `where` clause, like `level >= 300 & (gereqs = WRI | gereqs = EIN)`
+impl fmt::Display for Qualification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |values: &[Qualification], separator: &str| {
+            values
+                .iter()
+                .map(|v| match v {
+                    Qualification::Single(q) => q.to_string(),
+                    v => format!("({})", v),
+                })
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+
+        match self {
+            Qualification::Single(q) => write!(f, "{}", q),
+            Qualification::BooleanAnd(and) => write!(f, "{}", join(&and.values, " & ")),
+            Qualification::BooleanOr(or) => write!(f, "{}", join(&or.values, " | ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::evaluate::Course as FullCourse;
+    use serde_json::json;
+
+    use self::FieldNameEnum::*;
+    use self::QualificationOperator::*;
+
+    const OPERATORS: [QualificationOperator; 6] = [Lt, Lte, Eq, Neq, Gt, Gte];
+    const T: bool = true;
+    const F: bool = false;
+
+    fn course() -> FullCourse {
+        FullCourse {
+            clbid: "1".to_string(),
+            credits: ordered_float::OrderedFloat(1.0),
+            crsid: "1".to_string(),
+            department: vec!["AS".to_string(), "HI".to_string()],
+            gereqs: vec!["EIN".to_string(), "WRI".to_string()],
+            level: 200,
+            number: 250,
+            semester: 1,
+            year: 2014,
+            instructors: vec!["Jane Doe".to_string()],
+            course_type: Some("Lab".to_string()),
+            ..FullCourse::default()
+        }
+    }
+
+    fn number(value: i32) -> QualificationValue {
+        QualificationValue::Number(NumericValue { value })
+    }
+
+    fn string(value: &str) -> QualificationValue {
+        QualificationValue::String(StringValue {
+            value: value.to_string(),
+        })
+    }
+
+    fn or(values: Vec<StaticValue>) -> QualificationValue {
+        QualificationValue::BooleanOr(BooleanOrValue { values })
+    }
+
+    fn and(values: Vec<StaticValue>) -> QualificationValue {
+        QualificationValue::BooleanAnd(BooleanAndValue { values })
+    }
+
+    /// Checks the qualification under each operator, in the order of `OPERATORS`
+    fn check(key: FieldNameEnum, value: QualificationValue, expected: [bool; 6]) {
+        for (operator, expected) in OPERATORS.iter().zip(expected.iter()) {
+            let qualification = SingleQualification {
+                key,
+                value: value.clone(),
+                operator: *operator,
+                matched_courses: None,
+                result: None,
+            };
+
+            assert_eq!(
+                qualification == course(),
+                *expected,
+                "{:?} {:?} {:?}",
+                key,
+                operator,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn numeric_fields_vs_numbers() {
+        check(Year, number(2014), [F, T, T, F, F, T]);
+        check(Year, number(2015), [T, T, F, T, F, F]);
+        check(Year, number(2013), [F, F, F, T, T, T]);
+        check(Level, number(200), [F, T, T, F, F, T]);
+        check(Level, number(300), [T, T, F, T, F, F]);
+        check(Level, number(100), [F, F, F, T, T, T]);
+    }
+
+    #[test]
+    fn numeric_fields_vs_strings() {
+        check(Year, string("2014"), [F, T, T, F, F, T]);
+        check(Level, string("300"), [T, T, F, T, F, F]);
+        check(Level, string("abc"), [F, F, F, T, F, F]);
+    }
+
+    #[test]
+    fn numeric_fields_vs_boolean_values() {
+        check(Year, or(vec![json!(2013), json!(2014)]), [F, T, T, F, T, T]);
+        check(Year, or(vec![json!(2015), json!(2016)]), [T, T, F, T, F, F]);
+        check(
+            Year,
+            and(vec![json!(2013), json!(2014)]),
+            [F, F, F, T, F, T],
+        );
+        check(Level, and(vec![json!(200), json!(200)]), [F, T, T, F, F, T]);
+        check(Level, and(vec![json!(100), json!(150)]), [F, F, F, T, T, T]);
+    }
+
+    #[test]
+    fn list_fields_vs_strings() {
+        check(Department, string("AS"), [F, F, T, F, F, F]);
+        check(Department, string("CSCI"), [F, F, F, T, F, F]);
+        check(Department, string("ZZZ"), [F, F, F, T, F, F]);
+        check(GeReq, string("EIN"), [F, F, T, F, F, F]);
+        check(GeReq, string("BTS-T"), [F, F, F, T, F, F]);
+    }
+
+    #[test]
+    fn other_course_fields() {
+        check(Number, number(250), [F, T, T, F, F, T]);
+        check(Semester, string("1"), [F, T, T, F, F, T]);
+        check(Term, number(20141), [F, T, T, F, F, T]);
+        check(Credits, number(1), [F, T, T, F, F, T]);
+        check(Credits, string("0.25"), [F, F, F, T, T, T]);
+        check(Instructors, string("Jane Doe"), [F, F, T, F, F, F]);
+        check(Type, string("Lab"), [F, F, T, F, F, F]);
+        check(Pn, string("false"), [F, T, T, F, F, T]);
+        check(Pn, number(0), [F, F, F, T, F, F]);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let err = serde_json::from_value::<SingleQualification>(json!({
+            "key": "color", "operator": "Eq",
+            "value": {"type": "String", "value": "red"},
+            "matched_courses": null, "result": null
+        }))
+        .unwrap_err();
+
+        assert!(err.to_string().contains("`color`"), "{}", err);
+    }
+
+    #[test]
+    fn list_fields_vs_numbers() {
+        check(Department, number(100), [F, F, F, T, F, F]);
+        check(GeReq, number(100), [F, F, F, T, F, F]);
+    }
+
+    #[test]
+    fn list_fields_vs_boolean_values() {
+        check(
+            GeReq,
+            or(vec![json!("BTS-T"), json!("WRI")]),
+            [F, F, T, F, F, F],
+        );
+        check(
+            GeReq,
+            or(vec![json!("BTS-T"), json!("SPM")]),
+            [F, F, F, T, F, F],
+        );
+        check(
+            GeReq,
+            and(vec![json!("EIN"), json!("WRI")]),
+            [F, F, T, F, F, F],
+        );
+        check(
+            GeReq,
+            and(vec![json!("EIN"), json!("SPM")]),
+            [F, F, F, T, F, F],
+        );
+        check(
+            Department,
+            or(vec![json!("CSCI"), json!("HI")]),
+            [F, F, T, F, F, F],
+        );
+        check(
+            Department,
+            and(vec![json!("AS"), json!("HI")]),
+            [F, F, T, F, F, F],
+        );
+    }
+
+    #[test]
+    fn functions_compare_against_their_computed_value() {
+        let function = |computed_value| {
+            QualificationValue::Function(FunctionValue {
+                name: FunctionNameEnum::Min,
+                prop: Year,
+                qualifier: Box::new(Qualification::Single(SingleQualification {
+                    key: GeReq,
+                    value: string("BTS-T"),
+                    operator: Eq,
+                    matched_courses: None,
+                    result: None,
+                })),
+                computed_value,
+            })
+        };
+        check(Year, function(None), [F, F, F, F, F, F]);
+        check(Year, function(Some(json!(2013))), [F, F, F, T, T, T]);
+    }
+}
@@ -3,4 +3,7 @@ use serde_derive::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ReferenceExpression {
     pub requirement: String,
+
+    pub matched_courses: Option<Vec<crate::evaluate::Course>>,
+    pub result: Option<bool>,
 }
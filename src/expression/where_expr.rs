@@ -7,4 +7,7 @@ pub struct WhereExpression {
     pub qualification: Qualification,
     pub count: ExpressionCounter,
     pub distinct: bool,
+
+    pub matched_courses: Option<Vec<crate::evaluate::Course>>,
+    pub result: Option<bool>,
 }
@@ -0,0 +1,974 @@
+use super::ParseError;
+use crate::expression::counter::{ExpressionCounter, Operator, Shorthand};
+use crate::expression::course::CourseExpression;
+use crate::expression::filter::{FilterExpression, FilterOfExpression, FilterWhereExpression};
+use crate::expression::modifier;
+use crate::expression::qualification::*;
+use crate::expression::*;
+use serde_json::json;
+
+/// Parses the shorthand for a `result`, such as `CSCI 121 | 125` or `two of (A, B, C)`.
+///
+/// `declared` holds the names of the requirements which may be referenced from the expression,
+/// and which `children` stands for.
+pub fn parse_expression(input: &str, declared: &[String]) -> Result<HansonExpression, ParseError> {
+    let mut parser = Parser::new(input, declared, declared);
+    let expr = parser.or_expression()?;
+    parser.expect_end()?;
+    Ok(expr)
+}
+
+/// Parses a requirement's `result`, where `children` stands for the requirement's own children,
+/// while its siblings may only be referenced by name.
+pub(crate) fn parse_result(
+    input: &str,
+    children: &[String],
+    siblings: &[String],
+) -> Result<HansonExpression, ParseError> {
+    let mut declared = children.to_vec();
+    declared.extend_from_slice(siblings);
+
+    let mut parser = Parser::new(input, &declared, children);
+    let expr = parser.or_expression()?;
+    parser.expect_end()?;
+    Ok(expr)
+}
+
+/// Parses a single course, such as `CSCI 121`, `PHYS 130L` or `MATH 282.2014.1`.
+pub fn parse_course(input: &str) -> Result<CourseExpression, ParseError> {
+    let mut parser = Parser::new(input, &[], &[]);
+    let course = parser.course()?;
+    parser.expect_end()?;
+    Ok(course)
+}
+
+/// Parses the shorthand for a `filter`, such as `only courses from (CSCI 121, 125)`.
+pub fn parse_filter(input: &str) -> Result<FilterExpression, ParseError> {
+    let mut parser = Parser::new(input, &[], &[]);
+    let filter = parser.filter()?;
+    parser.expect_end()?;
+    Ok(filter)
+}
+
+const NUMBER_WORDS: [&str; 21] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+];
+
+/// The words which may follow a counter, used to tell `two of (…)` apart from a bare number
+const COUNTED_WORDS: [&str; 10] = [
+    "of",
+    "distinct",
+    "course",
+    "courses",
+    "credit",
+    "credits",
+    "department",
+    "departments",
+    "occurrence",
+    "occurrences",
+];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_value_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '/'
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    declared: &'a [String],
+    /// The names that `children` stands for
+    children: &'a [String],
+    last_department: Option<Vec<String>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, declared: &'a [String], children: &'a [String]) -> Parser<'a> {
+        Parser {
+            input,
+            pos: 0,
+            declared,
+            children,
+            last_department: None,
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let rest: String = self.rest().chars().take(20).collect();
+        let message = if rest.is_empty() {
+            format!("{} at end of input", message)
+        } else {
+            format!("{} near `{}`", message, rest.trim_end())
+        };
+
+        ParseError {
+            message,
+            requirement: vec![],
+            expression: Some(self.input.to_string()),
+            offset: Some(self.pos),
+            line: None,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, pred: F) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", token)))
+        }
+    }
+
+    fn peek_keyword(&mut self, word: &str) -> bool {
+        self.skip_whitespace();
+        let rest = self.rest();
+        rest.starts_with(word) && !rest[word.len()..].starts_with(is_word_char)
+    }
+
+    fn eat_keyword(&mut self, word: &str) -> bool {
+        if self.peek_keyword(word) {
+            self.pos += word.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, word: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(word) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", word)))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected input"))
+        }
+    }
+
+    fn or_expression(&mut self) -> Result<HansonExpression, ParseError> {
+        let mut values = vec![self.and_expression()?];
+        while self.eat("|") {
+            values.push(self.and_expression()?);
+        }
+
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+
+        Ok(HansonExpression::BooleanOr(BooleanOrExpression {
+            values,
+            matched_courses: None,
+            result: None,
+        }))
+    }
+
+    fn and_expression(&mut self) -> Result<HansonExpression, ParseError> {
+        let mut values = vec![self.primary()?];
+        while self.eat("&") {
+            values.push(self.primary()?);
+        }
+
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+
+        Ok(HansonExpression::BooleanAnd(BooleanAndExpression {
+            values,
+            matched_courses: None,
+            result: None,
+        }))
+    }
+
+    fn primary(&mut self) -> Result<HansonExpression, ParseError> {
+        if self.eat("(") {
+            let expr = self.or_expression()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+
+        if let Some(reference) = self.reference() {
+            return Ok(HansonExpression::Reference(reference));
+        }
+
+        if let Some(count) = self.counter()? {
+            return self.counted(count);
+        }
+
+        self.course().map(HansonExpression::Course)
+    }
+
+    /// Matches the longest declared requirement name at the current position
+    fn reference(&mut self) -> Option<ReferenceExpression> {
+        self.skip_whitespace();
+        let rest = self.rest();
+
+        let name = self
+            .declared
+            .iter()
+            .filter(|name| rest.starts_with(name.as_str()))
+            .filter(|name| !rest[name.len()..].starts_with(is_word_char))
+            .max_by_key(|name| name.len())?;
+
+        self.pos += name.len();
+
+        Some(ReferenceExpression {
+            requirement: name.clone(),
+            matched_courses: None,
+            result: None,
+        })
+    }
+
+    fn counter(&mut self) -> Result<Option<ExpressionCounter>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        let operator = if self.eat_keyword("at") {
+            if self.eat_keyword("least") {
+                Some(Operator::Gte)
+            } else if self.eat_keyword("most") {
+                Some(Operator::Lte)
+            } else {
+                return Err(self.error("expected `least` or `most`"));
+            }
+        } else if self.eat_keyword("exactly") {
+            Some(Operator::Eq)
+        } else {
+            None
+        };
+
+        self.skip_whitespace();
+        let word = self.take_while(is_word_char);
+
+        let count = match word {
+            "all" => Some(ExpressionCounter {
+                operator: Operator::Eq,
+                was: Some(Shorthand::All),
+                num: None,
+            }),
+            "any" => Some(ExpressionCounter {
+                operator: Operator::Gte,
+                was: Some(Shorthand::Any),
+                num: Some(1),
+            }),
+            "none" => Some(ExpressionCounter {
+                operator: Operator::Eq,
+                was: Some(Shorthand::None),
+                num: Some(0),
+            }),
+            _ => NUMBER_WORDS
+                .iter()
+                .position(|&w| w == word)
+                .map(|n| n as u32)
+                .or_else(|| word.parse::<u32>().ok())
+                .map(|num| ExpressionCounter {
+                    operator: operator.clone().unwrap_or(Operator::Gte),
+                    was: None,
+                    num: Some(num),
+                }),
+        };
+
+        match count {
+            Some(count) if COUNTED_WORDS.iter().any(|w| self.peek_keyword(w)) => Ok(Some(count)),
+            _ if operator.is_some() => Err(self.error("expected a count")),
+            _ => {
+                self.pos = start;
+                Ok(None)
+            }
+        }
+    }
+
+    fn counted(&mut self, count: ExpressionCounter) -> Result<HansonExpression, ParseError> {
+        if self.eat_keyword("of") {
+            let of = self.list(|p| p.or_expression())?;
+            return Ok(HansonExpression::Of(OfExpression {
+                count,
+                of,
+                matched_courses: None,
+                result: None,
+            }));
+        }
+
+        if self.eat_keyword("occurrences") || self.eat_keyword("occurrence") {
+            self.expect_keyword("of")?;
+            let course = self.course()?;
+            return Ok(HansonExpression::Occurrence(OccurrenceExpression {
+                course,
+                count,
+                matched_courses: None,
+                result: None,
+            }));
+        }
+
+        let distinct = self.eat_keyword("distinct");
+
+        let what = if self.eat_keyword("courses") || self.eat_keyword("course") {
+            modifier::WhatEnum::Course
+        } else if self.eat_keyword("credits") || self.eat_keyword("credit") {
+            modifier::WhatEnum::Credit
+        } else if self.eat_keyword("departments") || self.eat_keyword("department") {
+            modifier::WhatEnum::Department
+        } else {
+            return Err(self.error("expected `courses`, `credits` or `departments`"));
+        };
+
+        if self.eat_keyword("where") {
+            let qualification = self.qualification_block()?;
+
+            if let modifier::WhatEnum::Course = what {
+                return Ok(HansonExpression::Where(WhereExpression {
+                    qualification,
+                    count,
+                    distinct,
+                    matched_courses: None,
+                    result: None,
+                }));
+            }
+
+            let besides = self.besides()?;
+            return Ok(HansonExpression::Modifier(ModifierExpression::Where(
+                modifier::Where {
+                    count,
+                    what,
+                    besides,
+                    qualification,
+                    matched_courses: None,
+                    result: None,
+                },
+            )));
+        }
+
+        if distinct {
+            return Err(self.error("`distinct` may only be used with `where`"));
+        }
+
+        self.expect_keyword("from")?;
+        self.modifier(count, what).map(HansonExpression::Modifier)
+    }
+
+    fn modifier(
+        &mut self,
+        count: ExpressionCounter,
+        what: modifier::WhatEnum,
+    ) -> Result<ModifierExpression, ParseError> {
+        let children = if self.eat_keyword("children") {
+            Some(
+                self.children
+                    .iter()
+                    .map(|name| ReferenceExpression {
+                        requirement: name.clone(),
+                        matched_courses: None,
+                        result: None,
+                    })
+                    .collect(),
+            )
+        } else if self.peek_after_whitespace() == Some('(') {
+            Some(self.list(|p| match p.reference() {
+                Some(reference) => Ok(reference),
+                None => Err(p.error("expected the name of a requirement")),
+            })?)
+        } else {
+            None
+        };
+
+        if let Some(children) = children {
+            let expr = if self.eat_keyword("where") {
+                let qualification = self.qualification_block()?;
+                ModifierExpression::ChildrenWhere(modifier::ChildrenWhere {
+                    count,
+                    what,
+                    besides: self.besides()?,
+                    children,
+                    qualification,
+                    matched_courses: None,
+                    result: None,
+                })
+            } else {
+                ModifierExpression::Children(modifier::Children {
+                    count,
+                    what,
+                    besides: self.besides()?,
+                    children,
+                    matched_courses: None,
+                    result: None,
+                })
+            };
+            return Ok(expr);
+        }
+
+        if self.eat_keyword("filter") {
+            let expr = if self.eat_keyword("where") {
+                let qualification = self.qualification_block()?;
+                ModifierExpression::FilterWhere(modifier::FilterWhere {
+                    count,
+                    what,
+                    besides: self.besides()?,
+                    qualification,
+                    matched_courses: None,
+                    result: None,
+                })
+            } else {
+                ModifierExpression::Filter(modifier::Filter {
+                    count,
+                    what,
+                    besides: self.besides()?,
+                    matched_courses: None,
+                    result: None,
+                })
+            };
+            return Ok(expr);
+        }
+
+        if self.eat_keyword("courses") {
+            self.expect_keyword("where")?;
+            let qualification = self.qualification_block()?;
+            return Ok(ModifierExpression::Where(modifier::Where {
+                count,
+                what,
+                besides: self.besides()?,
+                qualification,
+                matched_courses: None,
+                result: None,
+            }));
+        }
+
+        Err(self.error("expected `children`, `filter`, `courses` or a list of requirements"))
+    }
+
+    fn besides(&mut self) -> Result<Option<CourseExpression>, ParseError> {
+        if self.eat_keyword("besides") {
+            self.course().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses a parenthesized, comma-separated list, allowing a trailing comma
+    fn list<T, F>(&mut self, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Parser<'a>) -> Result<T, ParseError>,
+    {
+        self.expect("(")?;
+
+        let mut items = vec![];
+        loop {
+            if self.eat(")") {
+                break;
+            }
+
+            items.push(item(self)?);
+
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+
+        if items.is_empty() {
+            return Err(self.error("expected at least one item in the list"));
+        }
+
+        Ok(items)
+    }
+
+    fn course(&mut self) -> Result<CourseExpression, ParseError> {
+        self.skip_whitespace();
+
+        let department = match self.peek() {
+            Some(c) if c.is_alphabetic() => {
+                let word = self.take_while(|c| c.is_alphabetic() || c == '/');
+                if word.chars().any(|c| c.is_lowercase()) {
+                    self.pos -= word.len();
+                    return Err(self.error(&format!(
+                        "`{}` is neither a declared requirement nor a department",
+                        word
+                    )));
+                }
+
+                let department: Vec<String> = word.split('/').map(String::from).collect();
+                self.last_department = Some(department.clone());
+                department
+            }
+            Some(c) if c.is_ascii_digit() => match &self.last_department {
+                Some(department) => department.clone(),
+                None => return Err(self.error("found a course number without a department")),
+            },
+            _ => return Err(self.error("expected a course, a requirement name, or a count")),
+        };
+
+        self.skip_whitespace();
+        let number = self.integer("a course number")?;
+
+        let lab = if self.rest().starts_with('L') && !self.rest()[1..].starts_with(is_word_char) {
+            self.pos += 1;
+            Some(true)
+        } else {
+            None
+        };
+
+        let (year, semester) = if self.rest().starts_with('.') {
+            self.pos += 1;
+            let year = self.integer("a year")?;
+            if !self.rest().starts_with('.') {
+                return Err(self.error("expected `.` and a semester"));
+            }
+            self.pos += 1;
+            let semester = self.integer("a semester")?;
+            (Some(year), Some(semester))
+        } else {
+            (None, None)
+        };
+
+        Ok(CourseExpression {
+            department,
+            number,
+            level: None,
+            semester,
+            year,
+            lab,
+            matched_courses: None,
+            result: None,
+        })
+    }
+
+    fn integer(&mut self, what: &str) -> Result<i32, ParseError> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits.parse().map_err(|_| {
+            self.pos -= digits.len();
+            self.error(&format!("expected {}", what))
+        })
+    }
+
+    fn filter(&mut self) -> Result<FilterExpression, ParseError> {
+        self.expect_keyword("only")?;
+        let distinct = self.eat_keyword("distinct");
+        self.expect_keyword("courses")?;
+
+        if self.eat_keyword("where") {
+            let qualification = self.qualification_block()?;
+            return Ok(FilterExpression::Where(FilterWhereExpression {
+                distinct,
+                qualification,
+            }));
+        }
+
+        if self.eat_keyword("from") {
+            let of = self.list(|p| p.course())?;
+            return Ok(FilterExpression::Of(FilterOfExpression { distinct, of }));
+        }
+
+        Err(self.error("expected `from` or `where`"))
+    }
+
+    fn qualification_block(&mut self) -> Result<Qualification, ParseError> {
+        self.expect("{")?;
+        let qualification = self.qualification_or()?;
+        self.expect("}")?;
+        Ok(qualification)
+    }
+
+    fn qualification_or(&mut self) -> Result<Qualification, ParseError> {
+        let mut values = vec![self.qualification_and()?];
+        while self.eat("|") {
+            values.push(self.qualification_and()?);
+        }
+
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+
+        Ok(Qualification::BooleanOr(OrQualification {
+            values,
+            matched_courses: None,
+            result: None,
+        }))
+    }
+
+    fn qualification_and(&mut self) -> Result<Qualification, ParseError> {
+        let mut values = vec![self.single_qualification()?];
+        while self.eat("&") {
+            values.push(self.single_qualification()?);
+        }
+
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+
+        Ok(Qualification::BooleanAnd(AndQualification {
+            values,
+            matched_courses: None,
+            result: None,
+        }))
+    }
+
+    fn single_qualification(&mut self) -> Result<Qualification, ParseError> {
+        if self.eat("(") {
+            let qualification = self.qualification_or()?;
+            self.expect(")")?;
+            return Ok(qualification);
+        }
+
+        let key = self.field_name()?;
+
+        let operator = if self.eat("<=") {
+            QualificationOperator::Lte
+        } else if self.eat(">=") {
+            QualificationOperator::Gte
+        } else if self.eat("!=") {
+            QualificationOperator::Neq
+        } else if self.eat("<") {
+            QualificationOperator::Lt
+        } else if self.eat(">") {
+            QualificationOperator::Gt
+        } else if self.eat("==") || self.eat("=") {
+            QualificationOperator::Eq
+        } else {
+            return Err(self.error("expected a comparison operator"));
+        };
+
+        let value = self.qualification_value()?;
+
+        Ok(Qualification::Single(SingleQualification {
+            key,
+            value,
+            operator,
+            matched_courses: None,
+            result: None,
+        }))
+    }
+
+    fn field_name(&mut self) -> Result<FieldNameEnum, ParseError> {
+        self.skip_whitespace();
+        let word = self.take_while(is_word_char);
+
+        word.parse().map_err(|message: String| {
+            self.pos -= word.len();
+            self.error(&message)
+        })
+    }
+
+    fn qualification_value(&mut self) -> Result<QualificationValue, ParseError> {
+        for (word, name) in &[
+            ("min", FunctionNameEnum::Min),
+            ("max", FunctionNameEnum::Max),
+        ] {
+            let start = self.pos;
+            if self.eat_keyword(word) && self.eat("(") {
+                let prop = self.field_name()?;
+                self.expect(")")?;
+                self.expect_keyword("from")?;
+                self.expect_keyword("courses")?;
+                self.expect_keyword("where")?;
+                let qualifier = self.qualification_block()?;
+
+                return Ok(QualificationValue::Function(FunctionValue {
+                    name: name.clone(),
+                    prop,
+                    qualifier: Box::new(qualifier),
+                    computed_value: None,
+                }));
+            }
+            self.pos = start;
+        }
+
+        if self.eat("(") {
+            let mut values = vec![self.static_value()?];
+            let is_and = self.peek_after_whitespace() == Some('&');
+            let separator = if is_and { "&" } else { "|" };
+            while self.eat(separator) {
+                values.push(self.static_value()?);
+            }
+            self.expect(")")?;
+
+            return Ok(if is_and {
+                QualificationValue::BooleanAnd(BooleanAndValue { values })
+            } else {
+                QualificationValue::BooleanOr(BooleanOrValue { values })
+            });
+        }
+
+        Ok(match self.static_value()? {
+            serde_json::Value::Number(n) => QualificationValue::Number(NumericValue {
+                value: n.as_i64().unwrap_or_default() as i32,
+            }),
+            value => QualificationValue::String(StringValue {
+                value: value.as_str().unwrap_or_default().to_string(),
+            }),
+        })
+    }
+
+    fn peek_after_whitespace(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.peek()
+    }
+
+    fn static_value(&mut self) -> Result<serde_json::Value, ParseError> {
+        self.skip_whitespace();
+
+        if self.rest().starts_with('"') {
+            let end = match self.rest()[1..].find('"') {
+                Some(end) => end,
+                None => return Err(self.error("unterminated string")),
+            };
+            let value = self.rest()[1..=end].to_string();
+            self.pos += end + 2;
+            return Ok(json!(value));
+        }
+
+        let word = self.take_while(is_value_char);
+        if word.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+
+        Ok(match word.parse::<i32>() {
+            Ok(n) => json!(n),
+            Err(_) => json!(word),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn department_carries_over() {
+        let expr = parse_expression("CSCI 121 | 125 | PHYS 130L", &[]).unwrap();
+
+        let courses = match expr {
+            HansonExpression::BooleanOr(or) => or.values,
+            other => panic!("expected an or-expression, got {:?}", other),
+        };
+
+        let parsed: Vec<(Vec<String>, i32, Option<bool>)> = courses
+            .into_iter()
+            .map(|c| match c {
+                HansonExpression::Course(c) => (c.department, c.number, c.lab),
+                other => panic!("expected a course, got {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(
+            parsed,
+            vec![
+                (names(&["CSCI"]), 121, None),
+                (names(&["CSCI"]), 125, None),
+                (names(&["PHYS"]), 130, Some(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn course_with_term_and_crosslisting() {
+        match parse_expression("MATH 282.2014.1", &[]).unwrap() {
+            HansonExpression::Course(c) => {
+                assert_eq!(c.year, Some(2014));
+                assert_eq!(c.semester, Some(1));
+            }
+            other => panic!("expected a course, got {:?}", other),
+        }
+
+        match parse_expression("AS/PS 245", &[]).unwrap() {
+            HansonExpression::Course(c) => assert_eq!(c.department, names(&["AS", "PS"])),
+            other => panic!("expected a course, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn of_with_references_containing_keywords() {
+        let declared = names(&["Theory of Computation", "Theory", "HiPerCiC"]);
+        let expr =
+            parse_expression("two of (Theory of Computation, HiPerCiC, )", &declared).unwrap();
+
+        match expr {
+            HansonExpression::Of(of) => {
+                assert_eq!(of.count.num, Some(2));
+                let refs: Vec<String> = of
+                    .of
+                    .into_iter()
+                    .map(|e| match e {
+                        HansonExpression::Reference(r) => r.requirement,
+                        other => panic!("expected a reference, got {:?}", other),
+                    })
+                    .collect();
+                assert_eq!(refs, names(&["Theory of Computation", "HiPerCiC"]));
+            }
+            other => panic!("expected an of-expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn where_with_function() {
+        let input = "one course where {
+            gereqs = EIN &
+            year >= min (year) from courses where { gereqs = BTS-T }
+        }";
+
+        match parse_expression(input, &[]).unwrap() {
+            HansonExpression::Where(expr) => match expr.qualification {
+                Qualification::BooleanAnd(and) => match &and.values[1] {
+                    Qualification::Single(q) => match &q.value {
+                        QualificationValue::Function(f) => match *f.qualifier {
+                            Qualification::Single(ref inner) => match &inner.value {
+                                QualificationValue::String(s) => assert_eq!(s.value, "BTS-T"),
+                                other => panic!("expected a string, got {:?}", other),
+                            },
+                            ref other => panic!("expected a qualification, got {:?}", other),
+                        },
+                        other => panic!("expected a function, got {:?}", other),
+                    },
+                    other => panic!("expected a qualification, got {:?}", other),
+                },
+                other => panic!("expected an and-qualification, got {:?}", other),
+            },
+            other => panic!("expected a where-expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn modifiers_and_occurrences() {
+        let input =
+            "six courses from filter & at most two courses from filter where { level = 100 }";
+        match parse_expression(input, &[]).unwrap() {
+            HansonExpression::BooleanAnd(and) => {
+                match &and.values[0] {
+                    HansonExpression::Modifier(ModifierExpression::Filter(f)) => {
+                        assert_eq!(f.count.num, Some(6))
+                    }
+                    other => panic!("expected a filter modifier, got {:?}", other),
+                }
+                match &and.values[1] {
+                    HansonExpression::Modifier(ModifierExpression::FilterWhere(f)) => {
+                        assert_eq!(f.count.num, Some(2));
+                        assert!(matches!(f.count.operator, Operator::Lte));
+                    }
+                    other => panic!("expected a filter-where modifier, got {:?}", other),
+                }
+            }
+            other => panic!("expected an and-expression, got {:?}", other),
+        }
+
+        match parse_expression("at least three occurrences of CSCI 300", &[]).unwrap() {
+            HansonExpression::Occurrence(o) => {
+                assert_eq!(o.count.num, Some(3));
+                assert_eq!(o.course.number, 300);
+            }
+            other => panic!("expected an occurrence, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn qualifications_on_other_fields() {
+        let input = "one course where { number >= 300 & pn = false & type = Lab }";
+
+        match parse_expression(input, &[]).unwrap() {
+            HansonExpression::Where(expr) => match expr.qualification {
+                Qualification::BooleanAnd(and) => {
+                    let keys: Vec<FieldNameEnum> = and
+                        .values
+                        .iter()
+                        .map(|q| match q {
+                            Qualification::Single(q) => q.key,
+                            other => panic!("expected a qualification, got {:?}", other),
+                        })
+                        .collect();
+                    assert_eq!(
+                        keys,
+                        vec![
+                            FieldNameEnum::Number,
+                            FieldNameEnum::Pn,
+                            FieldNameEnum::Type
+                        ]
+                    );
+                }
+                other => panic!("expected an and-qualification, got {:?}", other),
+            },
+            other => panic!("expected a where-expression, got {:?}", other),
+        }
+
+        let err = parse_expression("one course where { color = red }", &[]).unwrap_err();
+        assert!(err.message.contains("`color`"), "{}", err.message);
+        assert_eq!(err.offset, Some(19));
+    }
+
+    #[test]
+    fn undeclared_reference_is_an_error() {
+        let err = parse_expression("Req 1 & Req 2", &names(&["Req 1"])).unwrap_err();
+        assert!(err.message.contains("`Req`"), "{}", err.message);
+        assert_eq!(err.offset, Some(8));
+    }
+
+    #[test]
+    fn filters() {
+        match parse_filter("only courses from (ASIAN 121, 123, AS/SA 239)").unwrap() {
+            FilterExpression::Of(f) => assert_eq!(f.of.len(), 3),
+            other => panic!("expected a filter-of, got {:?}", other),
+        }
+
+        match parse_filter("only courses where { level >= 200 }").unwrap() {
+            FilterExpression::Where(_) => {}
+            other => panic!("expected a filter-where, got {:?}", other),
+        }
+    }
+}
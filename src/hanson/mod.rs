@@ -0,0 +1,360 @@
+use crate::evaluate::{AreaOfStudy, Requirement};
+use serde_yaml::{Mapping, Value};
+use std::fmt;
+
+mod expression;
+
+use self::expression::parse_result;
+pub use self::expression::{parse_course, parse_expression, parse_filter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// The names of the requirements enclosing the error, outermost first
+    pub requirement: Vec<String>,
+    /// The shorthand expression that the error was found in
+    pub expression: Option<String>,
+    /// The byte offset into the shorthand expression where the error was found
+    pub offset: Option<usize>,
+    /// The line of the area's source where the error was found, when it could be worked out
+    pub line: Option<usize>,
+}
+
+impl ParseError {
+    fn new(message: String) -> ParseError {
+        ParseError {
+            message,
+            requirement: vec![],
+            expression: None,
+            offset: None,
+            line: None,
+        }
+    }
+
+    fn within(mut self, name: &str) -> ParseError {
+        self.requirement.insert(0, name.to_string());
+        self
+    }
+
+    /// Works out which line of the area's source the error is on, by finding the expression
+    /// below the declaration of the requirement that it belongs to
+    fn locate(mut self, input: &str) -> ParseError {
+        if let (Some(expression), Some(offset)) = (&self.expression, self.offset) {
+            let mut from = 0;
+            for name in &self.requirement {
+                if let Some(found) = find_declaration(input, from, name) {
+                    from = found;
+                }
+            }
+
+            if let Some(start) = input[from..].find(expression.as_str()) {
+                let at = from + start + offset.min(expression.len());
+                self.line = Some(input[..at].matches('\n').count() + 1);
+            }
+        }
+
+        self
+    }
+}
+
+/// The byte offset of the first line, at or after `from`, that declares the requirement `name`
+fn find_declaration(input: &str, from: usize, name: &str) -> Option<usize> {
+    let key = format!("{}:", name);
+    let mut offset = from;
+
+    for line in input[from..].split_inclusive('\n') {
+        if line.trim_start().starts_with(&key) {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.requirement.is_empty() {
+            write!(f, "in requirement `{}`: ", self.requirement.join("/"))?;
+        }
+
+        write!(f, "{}", self.message)?;
+
+        // once the line is known, it's reported alongside the file instead
+        if let (Some(offset), None) = (self.offset, self.line) {
+            write!(f, " (at offset {})", offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the source of an area of study, as written by hand in YAML.
+///
+/// Keys that begin with an uppercase letter or a digit declare child requirements; everything
+/// else is an attribute of the area or requirement.
+pub fn parse_area(input: &str) -> Result<AreaOfStudy, ParseError> {
+    read_area(input).map_err(|err| err.locate(input))
+}
+
+fn read_area(input: &str) -> Result<AreaOfStudy, ParseError> {
+    let doc: Value = serde_yaml::from_str(input)
+        .map_err(|err| ParseError::new(format!("invalid YAML: {}", err)))?;
+
+    let map = match doc {
+        Value::Mapping(map) => map,
+        _ => return Err(ParseError::new("an area must be a mapping".to_string())),
+    };
+
+    let area_name = required_string(&map, "name")?;
+    let area_type = required_string(&map, "type")?;
+    let area_revision = required_string(&map, "revision")?;
+    let area_url = optional_string(&map, "slug")?.or_else(|| Some(slugify(&area_name)));
+
+    let children = parse_children(&map)?;
+    let declared: Vec<String> = children.iter().map(|r| r.name.clone()).collect();
+
+    let result = parse_expression(&required_string(&map, "result")?, &declared).map_err(|err| {
+        ParseError {
+            message: format!("in `result`: {}", err.message),
+            ..err
+        }
+    })?;
+
+    Ok(AreaOfStudy {
+        area_type,
+        area_name,
+        area_revision,
+        area_url,
+        result,
+        children,
+        evaluated: None,
+    })
+}
+
+fn is_requirement_key(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit())
+}
+
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+fn parse_children(map: &Mapping) -> Result<Vec<Requirement>, ParseError> {
+    let children: Vec<(&str, &Value)> = map
+        .iter()
+        .filter_map(|(key, value)| match key.as_str() {
+            Some(name) if is_requirement_key(name) => Some((name, value)),
+            _ => None,
+        })
+        .collect();
+
+    children
+        .iter()
+        .map(|&(name, value)| {
+            // a requirement may refer to any of its siblings, besides itself
+            let siblings: Vec<String> = children
+                .iter()
+                .map(|&(sibling, _)| sibling.to_string())
+                .filter(|sibling| sibling != name)
+                .collect();
+
+            parse_requirement(name, value, &siblings).map_err(|err| err.within(name))
+        })
+        .collect()
+}
+
+fn parse_requirement(
+    name: &str,
+    value: &Value,
+    siblings: &[String],
+) -> Result<Requirement, ParseError> {
+    let map = match value {
+        Value::String(result) => {
+            return Ok(Requirement {
+                name: name.to_string(),
+                result: Some(parse_result(result, &[], siblings)?),
+                message: None,
+                filter: None,
+                children_share_courses: None,
+                children: vec![],
+                evaluated: None,
+            });
+        }
+        Value::Mapping(map) => map,
+        _ => {
+            return Err(ParseError::new(
+                "a requirement must be either an expression or a mapping".to_string(),
+            ))
+        }
+    };
+
+    let children = parse_children(map)?;
+    let own: Vec<String> = children.iter().map(|r| r.name.clone()).collect();
+
+    let result = match optional_string(map, "result")? {
+        Some(result) => Some(parse_result(&result, &own, siblings)?),
+        None => None,
+    };
+
+    let filter = match optional_string(map, "filter")? {
+        Some(filter) => Some(parse_filter(&filter)?),
+        None => None,
+    };
+
+    let message = optional_string(map, "message")?;
+
+    if result.is_none() && message.is_none() {
+        return Err(ParseError::new(
+            "a requirement must have either a `result` or a `message`".to_string(),
+        ));
+    }
+
+    let children_share_courses = match map.get(&Value::from("children share courses")) {
+        Some(Value::Bool(share)) => Some(*share),
+        Some(_) => {
+            return Err(ParseError::new(
+                "`children share courses` must be a boolean".to_string(),
+            ))
+        }
+        None => None,
+    };
+
+    Ok(Requirement {
+        name: name.to_string(),
+        result,
+        message,
+        filter,
+        children_share_courses,
+        children,
+        evaluated: None,
+    })
+}
+
+fn optional_string(map: &Mapping, key: &str) -> Result<Option<String>, ParseError> {
+    match map.get(&Value::from(key)) {
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(Value::Number(n)) => Ok(Some(n.to_string())),
+        Some(_) => Err(ParseError::new(format!("`{}` must be a string", key))),
+        None => Ok(None),
+    }
+}
+
+fn required_string(map: &Mapping, key: &str) -> Result<String, ParseError> {
+    optional_string(map, key)?.ok_or_else(|| ParseError::new(format!("missing `{}`", key)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_area;
+    use crate::expression::modifier::ModifierExpression;
+    use crate::expression::HansonExpression;
+
+    #[test]
+    fn nested_requirements() {
+        let input = "
+name: Computer Science
+type: major
+revision: 2014-15
+result: all of (Foundation, Capstone)
+
+Foundation:
+    CS1:    CSCI 121 | 125
+    Design: CSCI 241 & 251 & 252
+
+    result: all of (CS1, Design)
+    children share courses: true
+
+Capstone:
+    CSCI 390
+";
+        let area = parse_area(input).unwrap();
+
+        assert_eq!(area.area_url, Some("computer-science".to_string()));
+        assert_eq!(area.children.len(), 2);
+
+        let foundation = &area.children[0];
+        assert_eq!(foundation.name, "Foundation");
+        assert_eq!(foundation.children_share_courses, Some(true));
+        assert_eq!(foundation.children[1].name, "Design");
+
+        match &area.children[1].result {
+            Some(HansonExpression::Course(c)) => assert_eq!(c.number, 390),
+            other => panic!("expected a course, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn errors_name_the_requirement() {
+        let input = "
+name: Broken
+type: major
+revision: 2011-12
+result: Outer
+
+Outer:
+    Inner: CSCI 121 |
+    result: Inner
+";
+        let err = parse_area(input).unwrap_err();
+        assert_eq!(err.requirement, vec!["Outer", "Inner"]);
+    }
+
+    #[test]
+    fn children_leaves_out_siblings() {
+        let input = "
+name: Computer Science
+type: major
+revision: 2014-15
+result: Core & Other
+
+Core:
+    Intro: CSCI 121
+    result: at least two courses from children
+
+Other: CSCI 125 | CSCI 126
+";
+        let area = parse_area(input).unwrap();
+
+        match &area.children[0].result {
+            Some(HansonExpression::Modifier(ModifierExpression::Children(modifier))) => {
+                let names: Vec<&str> = modifier
+                    .children
+                    .iter()
+                    .map(|r| r.requirement.as_str())
+                    .collect();
+                assert_eq!(names, vec!["Intro"]);
+            }
+            other => panic!("expected a modifier over children, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn errors_know_their_line() {
+        let input = "
+name: Broken
+type: major
+revision: 2011-12
+result: Outer & Other
+
+Other: CSCI 121 |
+
+Outer:
+    Other: CSCI 125
+    Inner: CSCI 121 |
+    result: Inner & Other
+";
+        let err = parse_area(input).unwrap_err();
+        assert_eq!(err.requirement, vec!["Other"]);
+        assert_eq!(err.line, Some(7));
+
+        let input = input.replace("Other: CSCI 121 |", "Other: CSCI 126");
+        let err = parse_area(&input).unwrap_err();
+        assert_eq!(err.requirement, vec!["Outer", "Inner"]);
+        assert_eq!(err.line, Some(11));
+    }
+}
@@ -0,0 +1,67 @@
+//! Checks a student's courses against the requirements of an area of study.
+//!
+//! ```no_run
+//! use gobbldygook_examine_rust::{audit, load_area, load_student, DEFAULT_BUDGET};
+//! use std::path::Path;
+//!
+//! # fn main() -> gobbldygook_examine_rust::Result<()> {
+//! let area = load_area(Path::new("examples/three-course-conflict/source.yaml"))?;
+//! let student = load_student(Path::new("examples/three-course-conflict/success.yaml"))?;
+//!
+//! let result = audit(area, &student, DEFAULT_BUDGET);
+//! assert!(result.evaluated.unwrap().success);
+//! # Ok(())
+//! # }
+//! ```
+
+mod compute;
+
+pub mod claim;
+pub mod course;
+pub mod dependency;
+pub mod error;
+pub mod evaluate;
+pub mod expression;
+pub mod hanson;
+pub mod lint;
+pub mod parse;
+pub mod path;
+pub mod plan;
+pub mod progress;
+pub mod recommend;
+pub mod remaining;
+pub mod schedule;
+pub mod solve;
+pub mod what_if;
+
+pub use crate::error::{Error, Result};
+pub use crate::evaluate::{
+    evaluate_area, AreaOfStudy, AreaOfStudyEvaluation, Course, Credits, Requirement,
+    RequirementEvaluation,
+};
+pub use crate::lint::{lint_area, Lint};
+pub use crate::parse::{
+    load_area, load_student, parse_area, parse_area_source, parse_student, Student,
+};
+pub use crate::path::{requirement_paths, RequirementPath};
+pub use crate::plan::{plan, Plan};
+pub use crate::progress::Progress;
+pub use crate::recommend::{recommend, Recommendation};
+pub use crate::remaining::{remaining_requirements, Remaining};
+pub use crate::schedule::{conflicts, Conflict};
+pub use crate::solve::{solve_area, DEFAULT_BUDGET};
+pub use crate::what_if::{what_if, WhatIf};
+
+/// Evaluates a student's courses, overrides and fulfillments against an area of study.
+///
+/// When requirements compete for the same courses, up to `budget` ways of handing them out are
+/// tried; see [`solve_area`](solve/fn.solve_area.html).
+pub fn audit(area_of_study: AreaOfStudy, student: &Student, budget: usize) -> AreaOfStudy {
+    solve_area(
+        &student.courses,
+        &student.overrides,
+        &student.fulfillments,
+        area_of_study,
+        budget,
+    )
+}
@@ -0,0 +1,438 @@
+use crate::dependency::evaluation_order;
+use crate::evaluate::{collect_references, compare_ordering, AreaOfStudy, Requirement};
+use crate::expression::counter::Operator;
+use crate::expression::filter::FilterExpression;
+use crate::expression::qualification::{
+    FieldNameEnum, Qualification, QualificationOperator, QualificationValue, SingleQualification,
+};
+use crate::expression::{HansonExpression, ModifierExpression};
+use crate::path::RequirementPath;
+use std::fmt;
+
+/// The levels that courses are offered at
+const COURSE_LEVELS: [i32; 4] = [100, 200, 300, 400];
+
+/// Fall, interim, spring, and the two summer sessions
+const SEMESTERS: [i32; 5] = [1, 2, 3, 4, 5];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// The requirement refers to something that is neither one of its children nor a sibling
+    DanglingReference(String),
+    /// The requirement can't be reached by following references from its parent's result, so it
+    /// can't affect the result
+    Unreferenced,
+    /// The requirement's children refer to each other in a loop
+    ReferenceCycle(Vec<String>),
+    /// The requirement has more than one child with this name
+    DuplicateName(String),
+    /// An `of` asks for more options than it has
+    CounterExceedsOptions { needed: usize, available: usize },
+    /// An `&` or `|` without anything in it
+    EmptyBoolean,
+    /// A qualification that no course could ever satisfy
+    ImpossibleQualification(String),
+}
+
+/// A problem with an area's definition, found without evaluating it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub requirement: RequirementPath,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.requirement)?;
+
+        match &self.problem {
+            Problem::DanglingReference(name) => {
+                write!(
+                    f,
+                    "refers to `{}`, which is neither one of its children nor one of its siblings",
+                    name
+                )
+            }
+            Problem::Unreferenced => write!(f, "can't be reached from its parent's result"),
+            Problem::ReferenceCycle(names) => {
+                let names: Vec<String> = names
+                    .iter()
+                    .chain(names.first())
+                    .map(|n| format!("`{}`", n))
+                    .collect();
+                write!(
+                    f,
+                    "has children that refer to each other in a cycle: {}",
+                    names.join(" -> ")
+                )
+            }
+            Problem::DuplicateName(name) => write!(f, "has more than one child named `{}`", name),
+            Problem::CounterExceedsOptions { needed, available } => write!(
+                f,
+                "asks for {} of {} options, which can never be satisfied",
+                needed, available
+            ),
+            Problem::EmptyBoolean => write!(f, "has an empty `&` or `|`"),
+            Problem::ImpossibleQualification(qualification) => {
+                write!(f, "`{}` can never match a course", qualification)
+            }
+        }
+    }
+}
+
+/// Checks an area's definition for mistakes that would quietly make it impossible to satisfy
+pub fn lint_area(area: &AreaOfStudy) -> Vec<Lint> {
+    let mut lints = vec![];
+
+    lint_requirement(
+        Some(&area.result),
+        None,
+        &area.children,
+        &[],
+        &RequirementPath::area(&area.area_name, &area.area_type),
+        &mut lints,
+    );
+
+    lints
+}
+
+fn lint_requirement(
+    result: Option<&HansonExpression>,
+    filter: Option<&FilterExpression>,
+    children: &[Requirement],
+    siblings: &[Requirement],
+    path: &RequirementPath,
+    lints: &mut Vec<Lint>,
+) {
+    let report = |lints: &mut Vec<Lint>, requirement: RequirementPath, problem: Problem| {
+        lints.push(Lint {
+            requirement,
+            problem,
+        })
+    };
+
+    let mut references = vec![];
+    if let Some(result) = result {
+        collect_references(result, &mut references);
+    }
+
+    for reference in &references {
+        if !children
+            .iter()
+            .chain(siblings)
+            .any(|r| &r.name == reference)
+        {
+            let problem = Problem::DanglingReference(reference.to_string());
+            report(lints, path.clone(), problem);
+        }
+    }
+
+    // a requirement without a result is only there to hold a message
+    if result.is_some() {
+        let reached = reachable_children(&references, children);
+
+        for child in children {
+            if !reached.contains(&child.name.as_str()) {
+                report(lints, path.child(&child.name), Problem::Unreferenced);
+            }
+        }
+    }
+
+    if let Err(cycle) = evaluation_order(children) {
+        let names = cycle.iter().map(|&i| children[i].name.clone()).collect();
+        report(lints, path.clone(), Problem::ReferenceCycle(names));
+    }
+
+    let mut seen: Vec<String> = vec![];
+    for child in children {
+        let name = child.name.to_lowercase();
+        if seen.contains(&name) {
+            let problem = Problem::DuplicateName(child.name.clone());
+            report(lints, path.clone(), problem);
+        } else {
+            seen.push(name);
+        }
+    }
+
+    if let Some(result) = result {
+        lint_expression(result, path, lints);
+    }
+
+    if let Some(FilterExpression::Where(filter)) = filter {
+        lint_qualification(&filter.qualification, path, lints);
+    }
+
+    for child in children {
+        lint_requirement(
+            child.result.as_ref(),
+            child.filter.as_ref(),
+            &child.children,
+            children,
+            &path.child(&child.name),
+            lints,
+        );
+    }
+}
+
+/// The names of the children that `references` reach, either directly or through the references
+/// of other children that they reach
+fn reachable_children<'a>(references: &[&'a str], children: &'a [Requirement]) -> Vec<&'a str> {
+    let mut reached: Vec<&str> = vec![];
+    let mut pending = references.to_vec();
+
+    while let Some(name) = pending.pop() {
+        if reached.contains(&name) {
+            continue;
+        }
+
+        let child = match children.iter().find(|c| c.name == name) {
+            Some(child) => child,
+            None => continue,
+        };
+        reached.push(&child.name);
+
+        if let Some(result) = &child.result {
+            let mut found = vec![];
+            collect_references(result, &mut found);
+
+            // a child's own children come before its siblings when its references are resolved
+            pending.extend(
+                found
+                    .into_iter()
+                    .filter(|&n| !child.children.iter().any(|c| c.name == n)),
+            );
+        }
+    }
+
+    reached
+}
+
+fn lint_expression(expr: &HansonExpression, path: &RequirementPath, lints: &mut Vec<Lint>) {
+    let mut report = |problem: Problem| {
+        lints.push(Lint {
+            requirement: path.clone(),
+            problem,
+        })
+    };
+
+    match expr {
+        HansonExpression::BooleanAnd(and) => {
+            if and.values.is_empty() {
+                report(Problem::EmptyBoolean);
+            }
+
+            for value in &and.values {
+                lint_expression(value, path, lints);
+            }
+        }
+        HansonExpression::BooleanOr(or) => {
+            if or.values.is_empty() {
+                report(Problem::EmptyBoolean);
+            }
+
+            for value in &or.values {
+                lint_expression(value, path, lints);
+            }
+        }
+        HansonExpression::Of(of) => {
+            let available = of.of.len();
+
+            if let Operator::Gte | Operator::Eq = of.count.operator {
+                let needed = of.count.required(available);
+                if needed > available {
+                    report(Problem::CounterExceedsOptions { needed, available });
+                }
+            }
+
+            for value in &of.of {
+                lint_expression(value, path, lints);
+            }
+        }
+        HansonExpression::Where(expr) => lint_qualification(&expr.qualification, path, lints),
+        HansonExpression::Modifier(ModifierExpression::Where(expr)) => {
+            lint_qualification(&expr.qualification, path, lints)
+        }
+        HansonExpression::Modifier(ModifierExpression::FilterWhere(expr)) => {
+            lint_qualification(&expr.qualification, path, lints)
+        }
+        HansonExpression::Modifier(ModifierExpression::ChildrenWhere(expr)) => {
+            lint_qualification(&expr.qualification, path, lints)
+        }
+        _ => {}
+    }
+}
+
+fn lint_qualification(
+    qualification: &Qualification,
+    path: &RequirementPath,
+    lints: &mut Vec<Lint>,
+) {
+    match qualification {
+        Qualification::Single(single) => {
+            if is_impossible(single) {
+                lints.push(Lint {
+                    requirement: path.clone(),
+                    problem: Problem::ImpossibleQualification(single.to_string()),
+                });
+            }
+
+            if let QualificationValue::Function(function) = &single.value {
+                lint_qualification(&function.qualifier, path, lints);
+            }
+        }
+        Qualification::BooleanOr(or) => {
+            for value in &or.values {
+                lint_qualification(value, path, lints);
+            }
+        }
+        Qualification::BooleanAnd(and) => {
+            for value in &and.values {
+                lint_qualification(value, path, lints);
+            }
+        }
+    }
+}
+
+/// The only values that a field can take, for the fields where we know them all
+fn possible_values(key: &FieldNameEnum) -> Option<&'static [i32]> {
+    match key {
+        FieldNameEnum::Level => Some(&COURSE_LEVELS),
+        FieldNameEnum::Semester => Some(&SEMESTERS),
+        _ => None,
+    }
+}
+
+fn is_impossible(qualification: &SingleQualification) -> bool {
+    // lists of names have no order to compare against
+    if qualification.key.is_list() {
+        let operator = qualification.operator;
+        return operator != QualificationOperator::Eq && operator != QualificationOperator::Neq;
+    }
+
+    let possible = match possible_values(&qualification.key) {
+        Some(possible) => possible,
+        None => return false,
+    };
+
+    let value = match &qualification.value {
+        QualificationValue::Number(n) => Some(n.value),
+        QualificationValue::String(s) => s.value.parse::<i32>().ok(),
+        _ => return false,
+    };
+
+    // the numeric fields never match a value that isn't a number, except to say they differ
+    match value {
+        Some(value) => !possible
+            .iter()
+            .any(|v| compare_ordering(v.cmp(&value), qualification.operator)),
+        None => qualification.operator != QualificationOperator::Neq,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lint_area, Problem};
+    use crate::hanson::parse_area;
+
+    fn problems(source: &str) -> Vec<(String, Problem)> {
+        lint_area(&parse_area(source).unwrap())
+            .into_iter()
+            .map(|lint| (lint.requirement.to_string(), lint.problem))
+            .collect()
+    }
+
+    #[test]
+    fn clean_areas_have_no_problems() {
+        let source = "
+name: Example
+type: major
+revision: 2018-19
+result: all of (Intro, Upper Level)
+
+Intro: CSCI 121 | CSCI 125
+Upper Level: two courses where { level >= 300 }
+";
+        assert_eq!(problems(source), vec![]);
+    }
+
+    #[test]
+    fn finds_mistakes() {
+        let source = "
+name: Example
+type: major
+revision: 2018-19
+result: three of (Intro, Graduate)
+
+Intro: CSCI 121 | CSCI 125
+Graduate: one course where { level >= 500 } | one course where { department > 100 }
+Forgotten: CSCI 390
+";
+        assert_eq!(
+            problems(source),
+            vec![
+                ("Example/major/Forgotten".to_string(), Problem::Unreferenced),
+                (
+                    "Example/major".to_string(),
+                    Problem::CounterExceedsOptions {
+                        needed: 3,
+                        available: 2
+                    }
+                ),
+                (
+                    "Example/major/Graduate".to_string(),
+                    Problem::ImpossibleQualification("level >= 500".to_string())
+                ),
+                (
+                    "Example/major/Graduate".to_string(),
+                    Problem::ImpossibleQualification("department > 100".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_children_that_only_unreachable_siblings_refer_to() {
+        let source = "
+name: Example
+type: major
+revision: 2018-19
+result: Intro
+
+Intro: CSCI 121 | Core
+Core: CSCI 251
+Forgotten: Also Forgotten
+Also Forgotten: CSCI 390
+";
+        assert_eq!(
+            problems(source),
+            vec![
+                ("Example/major/Forgotten".to_string(), Problem::Unreferenced),
+                (
+                    "Example/major/Also Forgotten".to_string(),
+                    Problem::Unreferenced
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_cycles_between_siblings() {
+        let source = "
+name: Example
+type: major
+revision: 2018-19
+result: Summary
+
+Summary: Intro | Electives
+Intro: Electives
+Electives: Intro
+";
+        assert_eq!(
+            problems(source),
+            vec![(
+                "Example/major".to_string(),
+                Problem::ReferenceCycle(vec!["Intro".to_string(), "Electives".to_string()])
+            )]
+        );
+    }
+}
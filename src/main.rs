@@ -2,74 +2,438 @@
 
 // extern crate test;
 
-mod compute;
-mod evaluate;
-mod expression;
-mod parse;
 mod print;
 
-use std::fs;
+use gobbldygook_examine_rust::parse::{load_courses, to_json, to_yaml};
+use gobbldygook_examine_rust::schedule::check_offerings;
+use gobbldygook_examine_rust::what_if::{next_term, parse_hypothetical};
+use gobbldygook_examine_rust::{
+    audit, conflicts, lint_area, load_area, load_student, plan, recommend, remaining_requirements,
+    requirement_paths, what_if, Result,
+};
 use std::path::PathBuf;
+use std::process;
 use structopt::StructOpt;
 
-/// A basic example
+/// Checks students' courses against the requirements of an area of study
 #[derive(StructOpt, Debug)]
-#[structopt(name = "basic")]
-struct Opts {
-    /// Activate debug mode
-    #[structopt(short = "d", long = "debug")]
-    debug: bool,
-
-    /// Activate debug mode
-    #[structopt(long = "debug-area")]
-    debug_area: bool,
-
-    /// Serialize result
-    #[structopt(long = "result")]
-    serialize_result: bool,
-
-    /// Output file
-    #[structopt(name = "AREA", parse(from_os_str))]
-    area_file: PathBuf,
-
-    /// Output file
-    #[structopt(name = "STUDENT", parse(from_os_str))]
-    student_file: PathBuf,
+#[structopt(name = "examine")]
+enum Opts {
+    /// Evaluate a student's courses against an area of study
+    #[structopt(name = "audit")]
+    Audit {
+        /// Activate debug mode
+        #[structopt(short = "d", long = "debug")]
+        debug: bool,
+
+        /// Print the parsed area before evaluating it
+        #[structopt(long = "debug-area")]
+        debug_area: bool,
+
+        /// Serialize result
+        #[structopt(long = "result")]
+        serialize_result: bool,
+
+        /// How many assignments of contested courses to try
+        #[structopt(long = "budget", default_value = "256")]
+        budget: usize,
+
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+
+        /// The student's courses, overrides and fulfillments
+        #[structopt(name = "STUDENT", parse(from_os_str))]
+        student_file: PathBuf,
+    },
+
+    /// List what a student still needs to satisfy an area of study
+    #[structopt(name = "remaining")]
+    Remaining {
+        /// How many assignments of contested courses to try
+        #[structopt(long = "budget", default_value = "256")]
+        budget: usize,
+
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+
+        /// The student's courses, overrides and fulfillments
+        #[structopt(name = "STUDENT", parse(from_os_str))]
+        student_file: PathBuf,
+    },
+
+    /// Show which requirements a student would satisfy by taking some more courses
+    #[structopt(name = "what-if")]
+    WhatIf {
+        /// How many assignments of contested courses to try
+        #[structopt(long = "budget", default_value = "256")]
+        budget: usize,
+
+        /// A course to take, written like `CSCI 253` or `CSCI 253.2019.3`; may be repeated, and
+        /// is required unless --courses is given
+        #[structopt(
+            long = "take",
+            number_of_values = 1,
+            raw(required_unless = "\"courses_file\"")
+        )]
+        take: Vec<String>,
+
+        /// A file of full course records to take, like the student's own courses
+        #[structopt(long = "courses", parse(from_os_str))]
+        courses_file: Option<PathBuf>,
+
+        /// The term to take the courses in, as `YEAR.SEMESTER`; defaults to the term after the
+        /// student's latest course
+        #[structopt(long = "term", parse(try_from_str = "parse_term"))]
+        term: Option<(i32, i32)>,
+
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+
+        /// The student's courses, overrides and fulfillments
+        #[structopt(name = "STUDENT", parse(from_os_str))]
+        student_file: PathBuf,
+    },
+
+    /// Rank the courses in a catalog by how many unmet requirements each would help satisfy
+    #[structopt(name = "recommend")]
+    Recommend {
+        /// How many assignments of contested courses to try
+        #[structopt(long = "budget", default_value = "256")]
+        budget: usize,
+
+        /// How many courses to list
+        #[structopt(long = "limit", default_value = "10")]
+        limit: usize,
+
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+
+        /// The student's courses, overrides and fulfillments
+        #[structopt(name = "STUDENT", parse(from_os_str))]
+        student_file: PathBuf,
+
+        /// The catalog's course listings, as a YAML or JSON list
+        #[structopt(name = "CATALOG", parse(from_os_str))]
+        catalog_file: PathBuf,
+    },
+
+    /// Plan the courses to take, term by term, to satisfy an area of study
+    #[structopt(name = "plan")]
+    Plan {
+        /// How many assignments of contested courses to try
+        #[structopt(long = "budget", default_value = "256")]
+        budget: usize,
+
+        /// How many courses to plan for a term at most
+        #[structopt(long = "load", default_value = "4")]
+        load: usize,
+
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+
+        /// The student's courses, overrides and fulfillments
+        #[structopt(name = "STUDENT", parse(from_os_str))]
+        student_file: PathBuf,
+
+        /// The catalog's course listings for the coming terms, as a YAML or JSON list
+        #[structopt(name = "CATALOG", parse(from_os_str))]
+        catalog_file: PathBuf,
+    },
+
+    /// List the courses in a student file that are taken in the same term and meet at the same
+    /// time
+    #[structopt(name = "conflicts")]
+    Conflicts {
+        /// The student's courses, overrides and fulfillments, like a plan written by `plan`
+        #[structopt(name = "STUDENT", parse(from_os_str))]
+        student_file: PathBuf,
+    },
+
+    /// Check an area for mistakes without evaluating it
+    #[structopt(name = "lint")]
+    Lint {
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+    },
+
+    /// Write an area out as expanded JSON, as read by every other command
+    #[structopt(name = "expand")]
+    Expand {
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+    },
+
+    /// List the path to every requirement in an area, for use as override and fulfillment keys
+    #[structopt(name = "paths")]
+    Paths {
+        /// The area of study, as Hanson source (.yaml) or expanded JSON
+        #[structopt(name = "AREA", parse(from_os_str))]
+        area_file: PathBuf,
+    },
+}
+
+/// Parses a term written like `2019.1`
+fn parse_term(input: &str) -> std::result::Result<(i32, i32), String> {
+    let invalid = || format!("expected a term like `2019.1`, not `{}`", input);
+
+    let mut parts = input.splitn(2, '.');
+    let year = parts.next().and_then(|y| y.parse().ok()).ok_or_else(invalid)?;
+    let semester = parts.next().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+
+    Ok((year, semester))
 }
 
 fn main() {
-    let opts = Opts::from_args();
+    match run(Opts::from_args()) {
+        Ok(code) => process::exit(code),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
 
-    let area_buf = fs::read_to_string(opts.area_file).expect("Unable to read file");
-    let student_buf = fs::read_to_string(opts.student_file).expect("Unable to read file");
+/// Runs the requested command, returning the process's exit code
+fn run(opts: Opts) -> Result<i32> {
+    match opts {
+        Opts::Audit {
+            debug,
+            debug_area,
+            serialize_result,
+            budget,
+            area_file,
+            student_file,
+        } => {
+            let area = load_area(&area_file)?;
+            let data = load_student(&student_file)?;
 
-    let area = parse::parse_area(area_buf);
-    let data = parse::parse_student(student_buf);
+            if debug_area {
+                println!("---");
+                println!("{:?}", area);
+            }
 
-    // println!("{:?}", data);
+            if debug {
+                println!("{}", to_yaml(&area)?);
+            }
 
-    if opts.debug_area {
-        println!("---");
-        println!("{:?}", area);
-    }
+            if debug {
+                println!("---");
+                println!("{}", to_json(&area)?);
+            }
 
-    if opts.debug {
-        println!("{}", serde_yaml::to_string(&area).unwrap());
-    }
+            let result = audit(area, &data, budget);
 
-    if opts.debug {
-        println!("---");
-        println!("{}", serde_json::to_string_pretty(&area).unwrap());
-    }
+            if serialize_result {
+                println!("{}", to_yaml(&result)?);
+            }
 
-    let result = evaluate::evaluate_area(&data.courses, &data.overrides, &data.fulfillments, area);
+            let has_error = match &result.evaluated {
+                Some(detail) => detail.error.is_some(),
+                None => false,
+            };
 
-    if opts.serialize_result {
-        println!("{}", serde_yaml::to_string(&result).unwrap());
-    }
+            print::print_student(&data);
+            print::print_area(result);
+
+            Ok(if has_error { 1 } else { 0 })
+        }
+        Opts::Remaining {
+            budget,
+            area_file,
+            student_file,
+        } => {
+            let area = load_area(&area_file)?;
+            let data = load_student(&student_file)?;
+
+            let result = audit(area, &data, budget);
+            let remaining = remaining_requirements(&result);
+
+            if remaining.is_empty() {
+                println!("Nothing remains; the area is satisfied.");
+            }
+
+            for requirement in &remaining {
+                print!("{}", requirement);
+            }
+
+            let has_error = match &result.evaluated {
+                Some(detail) => detail.error.is_some(),
+                None => false,
+            };
+
+            Ok(if has_error { 1 } else { 0 })
+        }
+        Opts::WhatIf {
+            budget,
+            take,
+            courses_file,
+            term,
+            area_file,
+            student_file,
+        } => {
+            let area = load_area(&area_file)?;
+            let data = load_student(&student_file)?;
+
+            let term = term.or_else(|| next_term(&data.courses));
+            let mut hypothetical = match &courses_file {
+                Some(path) => load_courses(path)?,
+                None => vec![],
+            };
+            if !take.is_empty() {
+                let term = match term {
+                    Some(term) => term,
+                    None => {
+                        eprintln!(
+                            "error: the student hasn't taken any courses, so there's no term \
+                             to take these in; pass --term"
+                        );
+                        return Ok(1);
+                    }
+                };
+
+                for course in &take {
+                    hypothetical.push(parse_hypothetical(course, term)?);
+                }
+            }
+
+            println!("If the student took:");
+            for course in &hypothetical {
+                println!(
+                    "- {} {} in {}.{}",
+                    course.department.join("/"),
+                    course.number,
+                    course.year,
+                    course.semester
+                );
+            }
+
+            let result = what_if(area, &data, &hypothetical, budget);
+
+            println!();
+            if result.newly_satisfied.is_empty() {
+                println!("No more requirements would be satisfied.");
+            } else {
+                println!("Now satisfied:");
+                for path in &result.newly_satisfied {
+                    println!("- {}", path);
+                }
+            }
+
+            if !result.newly_unsatisfied.is_empty() {
+                println!("No longer satisfied:");
+                for path in &result.newly_unsatisfied {
+                    println!("- {}", path);
+                }
+            }
+
+            let (success, has_error) = match &result.after.evaluated {
+                Some(detail) => (detail.success, detail.error.is_some()),
+                None => (false, false),
+            };
+
+            println!();
+            println!(
+                "The area would {}be satisfied.",
+                if success { "" } else { "not " }
+            );
 
-    print::print_student(&data);
-    print::print_area(result);
+            Ok(if has_error { 1 } else { 0 })
+        }
+        Opts::Recommend {
+            budget,
+            limit,
+            area_file,
+            student_file,
+            catalog_file,
+        } => {
+            let area = load_area(&area_file)?;
+            let data = load_student(&student_file)?;
+            let catalog = load_courses(&catalog_file)?;
+            check_offerings(&catalog).map_err(|err| err.in_file(&catalog_file))?;
+
+            let recommendations = recommend(&area, &data, &catalog, budget);
+
+            if recommendations.is_empty() {
+                println!("No course in the catalog would help with an unmet requirement.");
+            }
+
+            for recommendation in recommendations.iter().take(limit) {
+                print!("{}", recommendation);
+            }
+
+            Ok(0)
+        }
+        Opts::Plan {
+            budget,
+            load,
+            area_file,
+            student_file,
+            catalog_file,
+        } => {
+            let area = load_area(&area_file)?;
+            let data = load_student(&student_file)?;
+            let catalog = load_courses(&catalog_file)?;
+            check_offerings(&catalog).map_err(|err| err.in_file(&catalog_file))?;
+
+            let result = plan(&area, &data, &catalog, load, budget);
+
+            // the plan goes to stdout as a student file, so that it can be audited in turn
+            for term in &result.terms {
+                eprint!("{}", term);
+            }
+            if !result.satisfied {
+                eprintln!("The catalog doesn't have enough courses to satisfy the area.");
+            }
+
+            println!("{}", to_yaml(&result.student)?);
+
+            Ok(if result.satisfied { 0 } else { 1 })
+        }
+        Opts::Conflicts { student_file } => {
+            let data = load_student(&student_file)?;
+            check_offerings(&data.courses).map_err(|err| err.in_file(&student_file))?;
+
+            let found = conflicts(&data.courses);
+
+            for conflict in &found {
+                println!("{}", conflict);
+            }
+
+            Ok(if found.is_empty() { 0 } else { 1 })
+        }
+        Opts::Lint { area_file } => {
+            let area = load_area(&area_file)?;
+            let lints = lint_area(&area);
+
+            for lint in &lints {
+                println!("{}", lint);
+            }
+
+            Ok(if lints.is_empty() { 0 } else { 1 })
+        }
+        Opts::Expand { area_file } => {
+            let area = load_area(&area_file)?;
+            println!("{}", to_json(&area)?);
+
+            Ok(0)
+        }
+        Opts::Paths { area_file } => {
+            let area = load_area(&area_file)?;
+
+            for path in requirement_paths(&area) {
+                println!("{}", path);
+            }
+
+            Ok(0)
+        }
+    }
 }
 
 // #[cfg(test)]
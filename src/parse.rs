@@ -1,20 +1,241 @@
 use serde_derive::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
 use crate::evaluate::AreaOfStudy;
+use crate::hanson;
+use std::fs;
+use std::path::Path;
 
-pub fn parse_area(input: String) -> AreaOfStudy {
-    serde_json::from_str(&input).unwrap()
+/// The kinds of expression that the examiner knows how to evaluate
+const EXPRESSION_TYPES: &[&str] = &[
+    "BooleanAnd",
+    "BooleanOr",
+    "Course",
+    "Lab",
+    "Modifier",
+    "Occurrence",
+    "Of",
+    "Reference",
+    "Where",
+    "where",
+];
+
+/// Finds the first expression in a requirement's `result` whose type we don't support
+fn find_unsupported_expression(value: &serde_json::Value) -> Option<String> {
+    fn check_expression(expr: &serde_json::Value) -> Option<String> {
+        if let Some(kind) = expr.get("type").and_then(|t| t.as_str()) {
+            if !EXPRESSION_TYPES.contains(&kind) {
+                return Some(kind.to_string());
+            }
+        }
+
+        ["values", "of"]
+            .iter()
+            .filter_map(|key| expr.get(key).and_then(|v| v.as_array()))
+            .flat_map(|exprs| exprs.iter())
+            .find_map(check_expression)
+    }
+
+    value.get("result").and_then(check_expression).or_else(|| {
+        value
+            .get("children")
+            .and_then(|c| c.as_array())
+            .and_then(|children| children.iter().find_map(find_unsupported_expression))
+    })
+}
+
+/// Removes the " at line X column Y" that serde adds to its messages, since we report the
+/// location separately
+fn without_location(message: String) -> String {
+    match message.find(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message,
+    }
+}
+
+pub fn parse_area(input: String) -> Result<AreaOfStudy> {
+    serde_json::from_str(&input).map_err(|err| {
+        let line = Some(err.line()).filter(|&l| l > 0);
+
+        let unsupported = serde_json::from_str(&input)
+            .ok()
+            .and_then(|value: serde_json::Value| find_unsupported_expression(&value));
+
+        match unsupported {
+            Some(expression) => Error::UnsupportedExpression {
+                path: None,
+                line,
+                expression,
+            },
+            None => Error::MalformedArea {
+                path: None,
+                line,
+                column: Some(err.column()).filter(|&c| c > 0),
+                message: without_location(err.to_string()),
+            },
+        }
+    })
+}
+
+pub fn parse_area_source(input: String) -> Result<AreaOfStudy> {
+    hanson::parse_area(&input).map_err(|error| Error::Hanson {
+        path: None,
+        error: Box::new(error),
+    })
 }
 
 use crate::evaluate::{CourseList, FulfillmentMap, OverrideMap};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct DataStruct {
+pub struct Student {
     pub overrides: OverrideMap,
     pub courses: CourseList,
     pub fulfillments: FulfillmentMap,
 }
 
-pub fn parse_student(input: String) -> DataStruct {
-    serde_yaml::from_str(&input).unwrap()
+pub fn parse_student(input: String) -> Result<Student> {
+    serde_yaml::from_str(&input).map_err(|err| {
+        let location = err.location();
+
+        Error::MalformedStudent {
+            path: None,
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            message: without_location(err.to_string()),
+        }
+    })
+}
+
+/// Parses a YAML list of course records, as in a student's `courses`
+pub fn parse_courses(input: String) -> Result<CourseList> {
+    serde_yaml::from_str(&input).map_err(|err| {
+        let location = err.location();
+
+        Error::MalformedCourses {
+            path: None,
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            message: without_location(err.to_string()),
+        }
+    })
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Reads an area of study from a file; `.yaml` and `.yml` files are read as Hanson source, and
+/// anything else as expanded JSON
+pub fn load_area(path: &Path) -> Result<AreaOfStudy> {
+    let buf = read_file(path)?;
+
+    let area = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => parse_area_source(buf),
+        _ => parse_area(buf),
+    };
+
+    area.map_err(|err| err.in_file(path))
+}
+
+pub fn load_student(path: &Path) -> Result<Student> {
+    parse_student(read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+pub fn load_courses(path: &Path) -> Result<CourseList> {
+    parse_courses(read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Writes a value out as YAML, as in a student file
+pub fn to_yaml<T: serde::Serialize>(value: &T) -> Result<String> {
+    serde_yaml::to_string(value).map_err(|err| Error::Serialize {
+        format: "YAML",
+        message: err.to_string(),
+    })
+}
+
+/// Writes a value out as indented JSON, as in an expanded area
+pub fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string_pretty(value).map_err(|err| Error::Serialize {
+        format: "JSON",
+        message: err.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_area, parse_student, to_json};
+    use crate::error::Error;
+    use crate::expression::HansonExpression;
+
+    #[test]
+    fn reports_unsupported_expressions() {
+        let input = r#"{
+            "name": "Example", "type": "major", "revision": "2018-19", "slug": null,
+            "result": {"type": "Reference", "requirement": "Writing"},
+            "children": [{
+                "name": "Writing",
+                "result": {"type": "BooleanOr", "values": [
+                    {"type": "Proficiency", "department": ["ENGL"], "number": 150}
+                ]},
+                "children": []
+            }]
+        }"#;
+
+        match parse_area(input.to_string()) {
+            Err(Error::UnsupportedExpression { expression, .. }) => {
+                assert_eq!(expression, "Proficiency")
+            }
+            other => panic!("expected an unsupported expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_labs_as_courses() {
+        let input = r#"{
+            "name": "Example", "type": "major", "revision": "2018-19", "slug": null,
+            "result": {"type": "Lab", "department": ["PHYS"], "number": 130},
+            "children": []
+        }"#;
+
+        match parse_area(input.to_string()).unwrap().result {
+            HansonExpression::Course(course) => {
+                assert_eq!(course.number, 130);
+                assert_eq!(course.lab, Some(true));
+            }
+            other => panic!("expected a course, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_where_students_are_malformed() {
+        let input = "overrides: {}\nfulfillments: {}\ncourses:\n  - clbid: 1\n";
+
+        match parse_student(input.to_string()) {
+            Err(Error::MalformedStudent { line, message, .. }) => {
+                assert_eq!(line, Some(4));
+                assert!(!message.contains("at line"), "{}", message);
+            }
+            other => panic!("expected a malformed student, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_values_that_cannot_be_written() {
+        let mut terms = std::collections::BTreeMap::new();
+        terms.insert((2019, 1), "fall");
+
+        match to_json(&terms) {
+            Err(err @ Error::Serialize { .. }) => {
+                assert!(
+                    err.to_string().starts_with("unable to write JSON"),
+                    "{}",
+                    err
+                )
+            }
+            other => panic!("expected a serialization error, got {:?}", other),
+        }
+    }
 }
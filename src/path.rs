@@ -0,0 +1,189 @@
+use crate::evaluate::{AreaOfStudy, Requirement};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// The location of a requirement within an area of study, as in
+/// `Computer Science/major/Core/Theory`.
+///
+/// A path starts with the area's name and type, followed by the name of each requirement on the
+/// way down. Paths are compared without regard to case, so that the keys of a student's
+/// overrides and fulfillments don't have to match the area's capitalization exactly. A `/`
+/// inside of a name is written as `\/`, and a `\` as `\\`.
+#[derive(Debug, Clone)]
+pub struct RequirementPath {
+    parts: Vec<String>,
+}
+
+impl RequirementPath {
+    /// The path to the area itself, which every requirement's path begins with
+    pub fn area(area_name: &str, area_type: &str) -> RequirementPath {
+        RequirementPath {
+            parts: vec![area_name.to_string(), area_type.to_string()],
+        }
+    }
+
+    pub fn child(&self, name: &str) -> RequirementPath {
+        let mut parts = self.parts.clone();
+        parts.push(name.to_string());
+        RequirementPath { parts }
+    }
+
+    /// Whether this path is `other`, or leads to one of its descendants
+    pub fn is_within(&self, other: &RequirementPath) -> bool {
+        self.parts.len() >= other.parts.len() && self.key()[..other.parts.len()] == other.key()[..]
+    }
+
+    fn key(&self) -> Vec<String> {
+        self.parts.iter().map(|p| p.to_lowercase()).collect()
+    }
+}
+
+impl fmt::Display for RequirementPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self
+            .parts
+            .iter()
+            .map(|p| p.replace('\\', "\\\\").replace('/', "\\/"))
+            .collect();
+        write!(f, "{}", parts.join("/"))
+    }
+}
+
+impl FromStr for RequirementPath {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<RequirementPath, String> {
+        let mut parts = vec![];
+        let mut part = String::new();
+        let mut chars = input.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(escaped) => part.push(escaped),
+                    None => return Err(format!("`{}` ends with a lone `\\`", input)),
+                },
+                '/' => parts.push(std::mem::take(&mut part)),
+                _ => part.push(c),
+            }
+        }
+        parts.push(part);
+
+        let parts: Vec<String> = parts.iter().map(|p| p.trim().to_string()).collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return Err(format!("`{}` has an empty segment", input));
+        }
+
+        if parts.len() < 2 {
+            return Err(format!(
+                "`{}` must start with the area's name and type, as in `Asian Studies/major`",
+                input
+            ));
+        }
+
+        Ok(RequirementPath { parts })
+    }
+}
+
+impl PartialEq for RequirementPath {
+    fn eq(&self, other: &RequirementPath) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for RequirementPath {}
+
+impl PartialOrd for RequirementPath {
+    fn partial_cmp(&self, other: &RequirementPath) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RequirementPath {
+    fn cmp(&self, other: &RequirementPath) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Serialize for RequirementPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RequirementPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        input.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Lists the path to every requirement in the area, parents before their children
+pub fn requirement_paths(area: &AreaOfStudy) -> Vec<RequirementPath> {
+    fn walk(
+        requirements: &[Requirement],
+        path: &RequirementPath,
+        found: &mut Vec<RequirementPath>,
+    ) {
+        for req in requirements {
+            let path_to_here = path.child(&req.name);
+            found.push(path_to_here.clone());
+            walk(&req.children, &path_to_here, found);
+        }
+    }
+
+    let mut found = vec![];
+    walk(
+        &area.children,
+        &RequirementPath::area(&area.area_name, &area.area_type),
+        &mut found,
+    );
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::RequirementPath;
+
+    #[test]
+    fn round_trips_through_strings() {
+        let path: RequirementPath = "Computer Science/major/Core/ Theory ".parse().unwrap();
+        assert_eq!(path.to_string(), "Computer Science/major/Core/Theory");
+
+        let escaped = RequirementPath::area("Asian Studies", "major").child("Art/Architecture");
+        assert_eq!(
+            escaped.to_string(),
+            "Asian Studies/major/Art\\/Architecture"
+        );
+        assert_eq!(escaped.to_string().parse::<RequirementPath>(), Ok(escaped));
+
+        assert!("Computer Science".parse::<RequirementPath>().is_err());
+        assert!("Computer Science//Core".parse::<RequirementPath>().is_err());
+    }
+
+    #[test]
+    fn round_trips_names_with_slashes_and_backslashes() {
+        let area = RequirementPath::area("Asian Studies", "major");
+
+        for name in &["A\\B", "Ends With\\", "\\/", "Art/Architecture\\"] {
+            let path = area.child(name);
+            let parsed: RequirementPath = path.to_string().parse().unwrap();
+            assert_eq!(parsed.parts, path.parts, "{}", path);
+        }
+
+        assert_eq!(area.child("A\\B").to_string(), "Asian Studies/major/A\\\\B");
+    }
+
+    #[test]
+    fn compares_without_regard_to_case() {
+        let path: RequirementPath = "computer science/MAJOR/core".parse().unwrap();
+        let area = RequirementPath::area("Computer Science", "major");
+
+        assert_eq!(path, area.child("Core"));
+        assert!(path.is_within(&area));
+        assert!(!area.is_within(&path));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::audit;
+use crate::evaluate::{AreaOfStudy, Course};
+use crate::parse::Student;
+use crate::recommend::recommend;
+use crate::what_if::next_term;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// The courses planned for one term
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlannedTerm {
+    pub year: i32,
+    pub semester: i32,
+    pub courses: Vec<Course>,
+}
+
+impl fmt::Display for PlannedTerm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}.{}:", self.year, self.semester)?;
+
+        for course in &self.courses {
+            writeln!(f, "  - {} {}", course.department.join("/"), course.number)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A semester-by-semester plan for finishing an area of study
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Plan {
+    /// The terms that have courses planned in them, in order
+    pub terms: Vec<PlannedTerm>,
+    /// The student, with the planned courses added to the ones they've taken; this can be
+    /// written out and audited like any other student file
+    pub student: Student,
+    /// Whether the planned courses would satisfy the area
+    pub satisfied: bool,
+}
+
+/// The terms that the catalog offers courses in, from `first` on, in order
+fn catalog_terms(catalog: &[Course], first: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut terms: Vec<(i32, i32)> = catalog
+        .iter()
+        .map(|c| (c.year, c.semester))
+        .filter(|term| *term >= first)
+        .collect();
+
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Plans the courses a student could take, term by term, to satisfy an area of study.
+///
+/// Each catalog course is offered in the term given by its `year` and `semester`. Term by term,
+/// starting after the student's latest course, the planner adds the course that
+/// [`recommend`](../recommend/fn.recommend.html) ranks highest, until `load` courses are planned
+/// for the term or nothing on offer would help; it stops once the area is satisfied. Sections
+/// that meet at the same time as a course already planned for the term are passed over. This is a
+/// greedy search, so it doesn't promise the shortest plan, but since every pick is checked by
+/// evaluating the area again, a course only counts for what the evaluator would let it.
+pub fn plan(
+    area_of_study: &AreaOfStudy,
+    student: &Student,
+    catalog: &[Course],
+    load: usize,
+    budget: usize,
+) -> Plan {
+    let is_satisfied =
+        |student: &Student| match audit(area_of_study.clone(), student, budget).evaluated {
+            Some(detail) => detail.success,
+            None => false,
+        };
+
+    let first = next_term(&student.courses).unwrap_or((0, 0));
+    let mut planned = student.clone();
+    let mut terms = vec![];
+    let mut satisfied = is_satisfied(&planned);
+
+    for (year, semester) in catalog_terms(catalog, first) {
+        if satisfied {
+            break;
+        }
+
+        let offered: Vec<Course> = catalog
+            .iter()
+            .filter(|c| c.year == year && c.semester == semester)
+            .cloned()
+            .collect();
+
+        let mut courses = vec![];
+        while courses.len() < load && !satisfied {
+            let best = match recommend(area_of_study, &planned, &offered, budget)
+                .into_iter()
+                .next()
+            {
+                Some(recommendation) => recommendation.course,
+                None => break,
+            };
+
+            let course = Course {
+                term: best.term.or(Some(year * 10 + semester)),
+                ..best
+            };
+
+            planned.courses.push(course.clone());
+            courses.push(course);
+            satisfied = is_satisfied(&planned);
+        }
+
+        if !courses.is_empty() {
+            terms.push(PlannedTerm {
+                year,
+                semester,
+                courses,
+            });
+        }
+    }
+
+    Plan {
+        terms,
+        student: planned,
+        satisfied,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::plan;
+    use crate::course::course;
+    use crate::evaluate::{FulfillmentMap, OverrideMap};
+    use crate::hanson::parse_area;
+    use crate::parse::{parse_student, Student};
+
+    #[test]
+    fn plans_term_by_term_within_the_load() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: Intro & Core & Elective
+
+Intro: CSCI 121
+Core: CSCI 253 & CSCI 263
+Elective: CSCI 300 | CSCI 350
+",
+        )
+        .unwrap();
+
+        let student = Student {
+            courses: vec![course("CSCI", 121).in_term(2018, 1)],
+            overrides: OverrideMap::new(),
+            fulfillments: FulfillmentMap::new(),
+        };
+
+        let catalog = vec![
+            // offered before the student's next term, so never planned
+            course("CSCI", 300).in_term(2018, 1),
+            course("CSCI", 253).in_term(2018, 3),
+            course("ART", 102).in_term(2018, 3),
+            course("CSCI", 350).in_term(2018, 3),
+            course("CSCI", 263).in_term(2019, 1),
+            course("CSCI", 263).in_term(2020, 1),
+        ];
+
+        let result = plan(&area, &student, &catalog, 2, 16);
+        assert!(result.satisfied);
+
+        let terms: Vec<(i32, i32, Vec<i32>)> = result
+            .terms
+            .iter()
+            .map(|t| {
+                let numbers = t.courses.iter().map(|c| c.number).collect();
+                (t.year, t.semester, numbers)
+            })
+            .collect();
+        assert_eq!(terms, vec![(2018, 3, vec![350, 253]), (2019, 1, vec![263])]);
+        assert_eq!(result.terms[0].courses[0].term, Some(20183));
+
+        let written = serde_yaml::to_string(&result.student).unwrap();
+        let reread = parse_student(written).unwrap();
+        assert_eq!(reread.courses, result.student.courses);
+
+        let stuck = plan(&area, &student, &catalog[..3], 2, 16);
+        assert!(!stuck.satisfied);
+        assert_eq!(stuck.terms.len(), 1);
+    }
+
+    #[test]
+    fn plans_courses_named_by_the_area_itself() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: Intro & CSCI 253
+
+Intro: CSCI 121
+",
+        )
+        .unwrap();
+
+        let student = Student {
+            courses: vec![course("CSCI", 121).in_term(2018, 1)],
+            overrides: OverrideMap::new(),
+            fulfillments: FulfillmentMap::new(),
+        };
+
+        let catalog = vec![
+            course("ART", 102).in_term(2018, 3),
+            course("CSCI", 253).in_term(2018, 3),
+        ];
+
+        let result = plan(&area, &student, &catalog, 2, 16);
+        assert!(result.satisfied);
+
+        let numbers: Vec<i32> = result.terms[0].courses.iter().map(|c| c.number).collect();
+        assert_eq!(numbers, vec![253]);
+    }
+}
@@ -1,16 +1,25 @@
-use crate::evaluate::{AreaOfStudy, Requirement};
-use crate::expression::HansonExpression;
-use crate::parse::DataStruct;
+use gobbldygook_examine_rust::expression::modifier::WhatEnum;
+use gobbldygook_examine_rust::expression::{
+    HansonExpression, ModifierExpression, ReferenceExpression,
+};
+use gobbldygook_examine_rust::{AreaOfStudy, Course, Requirement, Student};
 // use crate::expressions;
 
-pub fn print_student(data: &DataStruct) {
+fn describe_course(course: &Course) -> String {
+    match &course.title {
+        Some(title) => format!("{} {}", course, title),
+        None => course.to_string(),
+    }
+}
+
+pub fn print_student(data: &Student) {
     println!("Available courses:");
 
     for c in data.courses.clone() {
-        println!("{}", c);
+        println!("{}", describe_course(&c));
     }
 
-    println!("");
+    println!();
 }
 
 pub fn print_area(area_of_study: AreaOfStudy) {
@@ -24,19 +33,53 @@ pub fn print_area(area_of_study: AreaOfStudy) {
             if detail.success { "Success" } else { "Failure" }
         );
 
-        let (at, of) = detail.progress;
-        println!("Progress: {} of {}", at, of);
+        println!("Progress: {}", detail.progress);
+        println!("Credits: {}", detail.credits_matched);
+
+        if let Some(error) = detail.error {
+            println!("Error: {}", error);
+        }
+
+        if detail.alternatives.len() > 1 {
+            println!(
+                "Tried {} assignments of courses:",
+                detail.alternatives.len()
+            );
+
+            for alternative in detail.alternatives {
+                let reservations = alternative
+                    .reservations
+                    .iter()
+                    .map(|r| format!("{} for {}", r.course, r.claimant))
+                    .collect::<Vec<String>>();
+
+                println!(
+                    "- {}: {}, {}",
+                    if reservations.is_empty() {
+                        "first come, first served".to_string()
+                    } else {
+                        reservations.join(", ")
+                    },
+                    if alternative.success {
+                        "Success"
+                    } else {
+                        "Failure"
+                    },
+                    alternative.progress
+                );
+            }
+        }
     } else {
         println!("Status: Not Evaluated");
     }
 
-    println!("");
+    println!();
     println!("## Requirements ##");
-    println!("");
+    println!();
 
     for child in area_of_study.children {
         print_requirement(child);
-        println!("");
+        println!();
     }
 }
 
@@ -45,6 +88,25 @@ fn print_requirement(req: Requirement) {
 
     if let Some(detail) = req.evaluated {
         println!("Status: {}", detail.success);
+
+        println!("Progress: {}", detail.progress);
+        println!("Credits: {}", detail.credits_matched);
+
+        if let Some(fulfillment) = detail.applied_fulfillment {
+            println!("Fulfilled by: {}", describe_course(&fulfillment));
+        }
+
+        for course in detail.claimed_courses {
+            println!("Claimed: {}", describe_course(&course));
+        }
+
+        for claim in detail.unavailable_courses {
+            println!(
+                "Unavailable: {} (claimed by {})",
+                describe_course(&claim.course),
+                claim.claimant
+            );
+        }
     }
 
     if let Some(result) = req.result {
@@ -52,14 +114,14 @@ fn print_requirement(req: Requirement) {
     }
 
     if !req.children.is_empty() {
-        println!("");
+        println!();
         println!("## Inner Requirements ##");
 
         for child in req.children {
             print_requirement(child);
         }
 
-        println!("");
+        println!();
     }
 }
 
@@ -69,8 +131,8 @@ fn print_expression(expr: HansonExpression) -> String {
             format!("(course {} {})", course.department.join("/"), course.number)
         }
         HansonExpression::Of(of) => format!(
-            "have N of {}:\n\t{}",
-            of.count.num.unwrap_or(0),
+            "have {} of:\n\t{}",
+            of.count,
             of.of
                 .into_iter()
                 .map(print_expression)
@@ -93,11 +155,54 @@ fn print_expression(expr: HansonExpression) -> String {
                 .collect::<Vec<String>>()
                 .join("\n\t| ")
         ),
-        HansonExpression::Modifier(_) => "<a modifier>".to_string(),
-        HansonExpression::Occurrence(_) => "<an occurrence>".to_string(),
+        HansonExpression::Modifier(modifier) => print_modifier(modifier),
+        HansonExpression::Occurrence(occurrence) => format!(
+            "{} occurrences of (course {} {})",
+            occurrence.count,
+            occurrence.course.department.join("/"),
+            occurrence.course.number
+        ),
         HansonExpression::Where(_) => "<a where>".to_string(),
         HansonExpression::Reference(reference) => {
             format!("<a reference to {}>", reference.requirement)
         }
     }
 }
+
+fn print_modifier(modifier: ModifierExpression) -> String {
+    let what = |what: WhatEnum| match what {
+        WhatEnum::Course => "courses",
+        WhatEnum::Credit => "credits",
+        WhatEnum::Department => "departments",
+    };
+
+    let children = |children: Vec<ReferenceExpression>| {
+        children
+            .into_iter()
+            .map(|c| c.requirement)
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    match modifier {
+        ModifierExpression::Where(m) => {
+            format!("{} {} from courses where {{…}}", m.count, what(m.what))
+        }
+        ModifierExpression::Filter(m) => format!("{} {} from filter", m.count, what(m.what)),
+        ModifierExpression::FilterWhere(m) => {
+            format!("{} {} from filter where {{…}}", m.count, what(m.what))
+        }
+        ModifierExpression::Children(m) => format!(
+            "{} {} from ({})",
+            m.count,
+            what(m.what),
+            children(m.children)
+        ),
+        ModifierExpression::ChildrenWhere(m) => format!(
+            "{} {} from ({}) where {{…}}",
+            m.count,
+            what(m.what),
+            children(m.children)
+        ),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::course::Credits;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+
+/// How far an expression or requirement has come towards being satisfied.
+///
+/// Each tally is a `(have, need)` pair, where `have` never exceeds `need`. The `fraction` is the
+/// share of everything needed that has been matched, counting each credit as much as a course
+/// or a requirement, and is `1.0` when nothing at all is needed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub courses: (usize, usize),
+    pub credits: (Credits, Credits),
+    /// Sub-requirements satisfied, out of those referred to
+    pub requirements: (usize, usize),
+    pub fraction: f64,
+}
+
+impl Progress {
+    fn new(
+        courses: (usize, usize),
+        credits: (Credits, Credits),
+        requirements: (usize, usize),
+    ) -> Progress {
+        let have = courses.0 as f64 + f64::from(credits.0) + requirements.0 as f64;
+        let need = courses.1 as f64 + f64::from(credits.1) + requirements.1 as f64;
+
+        Progress {
+            courses,
+            credits,
+            requirements,
+            fraction: if need > 0.0 { have / need } else { 1.0 },
+        }
+    }
+
+    pub fn courses(have: usize, need: usize) -> Progress {
+        Progress::new((have.min(need), need), Default::default(), (0, 0))
+    }
+
+    pub fn credits(have: Credits, need: Credits) -> Progress {
+        Progress::new((0, 0), (have.min(need), need), (0, 0))
+    }
+
+    pub fn requirements(have: usize, need: usize) -> Progress {
+        Progress::new((0, 0), Default::default(), (have.min(need), need))
+    }
+
+    /// The same needs, with all of them met; used when a requirement has been overridden
+    pub fn completed(self) -> Progress {
+        Progress::new(
+            (self.courses.1, self.courses.1),
+            (self.credits.1, self.credits.1),
+            (self.requirements.1, self.requirements.1),
+        )
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Progress {
+        Progress::new((0, 0), Default::default(), (0, 0))
+    }
+}
+
+impl Add for Progress {
+    type Output = Progress;
+
+    fn add(self, other: Progress) -> Progress {
+        Progress::new(
+            (
+                self.courses.0 + other.courses.0,
+                self.courses.1 + other.courses.1,
+            ),
+            (
+                self.credits.0 + other.credits.0,
+                self.credits.1 + other.credits.1,
+            ),
+            (
+                self.requirements.0 + other.requirements.0,
+                self.requirements.1 + other.requirements.1,
+            ),
+        )
+    }
+}
+
+impl Sum for Progress {
+    fn sum<I: Iterator<Item = Progress>>(iter: I) -> Progress {
+        iter.fold(Progress::default(), Add::add)
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec![];
+
+        if self.courses.1 > 0 {
+            parts.push(format!("{} of {} courses", self.courses.0, self.courses.1));
+        }
+        if self.credits.1 > Credits::default() {
+            parts.push(format!("{} of {} credits", self.credits.0, self.credits.1));
+        }
+        if self.requirements.1 > 0 {
+            parts.push(format!(
+                "{} of {} requirements",
+                self.requirements.0, self.requirements.1
+            ));
+        }
+
+        if parts.is_empty() {
+            return write!(f, "nothing needed");
+        }
+
+        write!(f, "{} ({:.0}%)", parts.join(", "), self.fraction * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Progress;
+    use crate::course::Credits;
+
+    #[test]
+    fn combines_every_kind_of_need() {
+        let progress = Progress::courses(1, 2)
+            + Progress::credits(Credits::from(1.5), Credits::whole(2))
+            + Progress::requirements(3, 2);
+
+        assert_eq!(progress.requirements, (2, 2));
+        assert_eq!(progress.fraction, 4.5 / 6.0);
+        assert_eq!(
+            progress.to_string(),
+            "1 of 2 courses, 1.5 of 2 credits, 2 of 2 requirements (75%)"
+        );
+
+        assert_eq!(progress.completed().fraction, 1.0);
+        assert_eq!(Progress::default().fraction, 1.0);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::audit;
+use crate::course::CourseStatus;
+use crate::evaluate::{AreaOfStudy, Course};
+use crate::parse::Student;
+use crate::path::RequirementPath;
+use crate::schedule::clash;
+use crate::what_if::{outcomes, Outcome};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// A catalog course, and the unmet requirements that taking it would help with
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Recommendation {
+    pub course: Course,
+    /// The unmet requirements that would come closer to being satisfied, including those in
+    /// `satisfies`; the area itself is listed too, while it's unmet
+    pub helps: Vec<RequirementPath>,
+    /// The unmet requirements that the course would finish off
+    pub satisfies: Vec<RequirementPath>,
+}
+
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.course.department.join("/"),
+            self.course.number
+        )?;
+
+        if let Some(title) = &self.course.title {
+            write!(f, " ({})", title)?;
+        }
+
+        writeln!(
+            f,
+            ": helps {}, satisfies {}",
+            self.helps.len(),
+            self.satisfies.len()
+        )?;
+
+        for path in &self.helps {
+            let mark = if self.satisfies.contains(path) {
+                "*"
+            } else {
+                "-"
+            };
+            writeln!(f, "  {} {}", mark, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether two records are the same course, like two sections of it, or a catalog listing and
+/// the course as the student took it
+fn same_course(a: &Course, b: &Course) -> bool {
+    a.department == b.department && a.number == b.number
+}
+
+/// Whether the catalog lists a section that can still be signed up for
+fn is_offered(course: &Course) -> bool {
+    match course.status {
+        Some(CourseStatus::Closed) | Some(CourseStatus::Cancelled) => false,
+        Some(CourseStatus::Open) | None => true,
+    }
+}
+
+/// The catalog courses that the student could take next: open sections of courses they haven't
+/// already taken, one section per course.
+///
+/// Sections that meet at the same time as one of the student's courses in that term are
+/// skipped, so that another section of the course can be picked instead.
+pub fn candidates<'a>(catalog: &'a [Course], student: &Student) -> Vec<&'a Course> {
+    let mut found: Vec<&Course> = vec![];
+
+    for course in catalog.iter().filter(|c| is_offered(c)) {
+        let taken = student.courses.iter().any(|c| same_course(c, course));
+        let listed = found.iter().any(|c| same_course(c, course));
+        let clashes = student.courses.iter().any(|c| clash(c, course).is_some());
+
+        if !taken && !listed && !clashes {
+            found.push(course);
+        }
+    }
+
+    found
+}
+
+/// Ranks the catalog's courses by how many of the area's unmet requirements each would help
+/// satisfy, most first.
+///
+/// Each candidate is added to the student's courses on its own and the area is evaluated again,
+/// so a course only counts towards the requirements that the evaluator would really let it;
+/// courses that wouldn't help with anything are left out.
+pub fn recommend(
+    area_of_study: &AreaOfStudy,
+    student: &Student,
+    catalog: &[Course],
+    budget: usize,
+) -> Vec<Recommendation> {
+    // the area's own result can name courses directly, so it counts like any other requirement
+    let unmet: Vec<Outcome> = outcomes(&audit(area_of_study.clone(), student, budget))
+        .into_iter()
+        .filter(|o| !o.success)
+        .collect();
+
+    let mut recommendations = vec![];
+
+    for course in candidates(catalog, student) {
+        let mut with_course = student.clone();
+        with_course.courses.push(course.clone());
+        let after = outcomes(&audit(area_of_study.clone(), &with_course, budget));
+
+        let mut helps = vec![];
+        let mut satisfies = vec![];
+
+        for before in &unmet {
+            if let Some(now) = after.iter().find(|o| o.path == before.path) {
+                if now.success {
+                    satisfies.push(now.path.clone());
+                }
+                if now.success || now.fraction > before.fraction {
+                    helps.push(now.path.clone());
+                }
+            }
+        }
+
+        if !helps.is_empty() {
+            recommendations.push(Recommendation {
+                course: course.clone(),
+                helps,
+                satisfies,
+            });
+        }
+    }
+
+    // a stable sort, so courses that help equally keep their catalog order
+    recommendations.sort_by(|a, b| {
+        (b.helps.len(), b.satisfies.len()).cmp(&(a.helps.len(), a.satisfies.len()))
+    });
+
+    recommendations
+}
+
+#[cfg(test)]
+mod test {
+    use super::{candidates, recommend};
+    use crate::course::{course, CourseStatus, DayOfWeek, Offering};
+    use crate::evaluate::{Course, FulfillmentMap, OverrideMap};
+    use crate::hanson::parse_area;
+    use crate::parse::{parse_courses, parse_student, Student};
+
+    #[test]
+    fn ranks_courses_by_what_they_help_with() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: Intro & Core & Elective
+
+Intro: CSCI 121
+Core: CSCI 253 & CSCI 263
+Elective: CSCI 263 | CSCI 300
+",
+        )
+        .unwrap();
+
+        let student = Student {
+            courses: vec![course("CSCI", 121)],
+            overrides: OverrideMap::new(),
+            fulfillments: FulfillmentMap::new(),
+        };
+
+        let catalog = vec![
+            course("CSCI", 121).with_status(CourseStatus::Open),
+            course("CSCI", 300).with_status(CourseStatus::Open),
+            course("CSCI", 253).with_status(CourseStatus::Cancelled),
+            course("CSCI", 253).with_status(CourseStatus::Open),
+            course("CSCI", 253).with_status(CourseStatus::Open),
+            course("CSCI", 263).with_status(CourseStatus::Closed),
+            course("ART", 102).with_status(CourseStatus::Open),
+        ];
+
+        let offered: Vec<i32> = candidates(&catalog, &student)
+            .iter()
+            .map(|c| c.number)
+            .collect();
+        assert_eq!(offered, vec![300, 253, 102]);
+
+        let recommendations = recommend(&area, &student, &catalog, 16);
+        let ranked: Vec<(i32, usize, usize)> = recommendations
+            .iter()
+            .map(|r| (r.course.number, r.helps.len(), r.satisfies.len()))
+            .collect();
+        assert_eq!(ranked, vec![(300, 2, 1), (253, 2, 0)]);
+
+        assert_eq!(
+            recommendations[1].to_string(),
+            "CSCI 253: helps 2, satisfies 0\n  - Computer Science/major\n  - Computer Science/major/Core\n"
+        );
+    }
+
+    #[test]
+    fn skips_sections_that_clash() {
+        let monday = |start: &str, end: &str| Offering {
+            day: DayOfWeek::Mo,
+            start: start.to_string(),
+            end: end.to_string(),
+            location: None,
+        };
+
+        let student = Student {
+            courses: vec![Course {
+                offerings: vec![monday("0905", "1000")],
+                ..course("CSCI", 121)
+            }],
+            overrides: OverrideMap::new(),
+            fulfillments: FulfillmentMap::new(),
+        };
+
+        let catalog = vec![
+            Course {
+                section: Some("A".to_string()),
+                offerings: vec![monday("0930", "1030")],
+                ..course("CSCI", 253).with_status(CourseStatus::Open)
+            },
+            Course {
+                section: Some("B".to_string()),
+                offerings: vec![monday("1045", "1200")],
+                ..course("CSCI", 253).with_status(CourseStatus::Open)
+            },
+        ];
+
+        let offered: Vec<Option<String>> = candidates(&catalog, &student)
+            .iter()
+            .map(|c| c.section.clone())
+            .collect();
+        assert_eq!(offered, vec![Some("B".to_string())]);
+    }
+
+    #[test]
+    fn counts_courses_named_by_the_area_itself() {
+        let area = parse_area(
+            "
+name: Problematic
+type: major
+revision: 2011-12
+result: Req & PHIL 103
+
+Req: PHIL 101 | PHIL 102
+",
+        )
+        .unwrap();
+
+        let student = parse_student(
+            include_str!("../examples/three-course-conflict/failure.yaml").to_string(),
+        )
+        .unwrap();
+        let catalog = parse_courses(
+            include_str!("../examples/three-course-conflict/catalog.yaml").to_string(),
+        )
+        .unwrap();
+
+        let recommendations = recommend(&area, &student, &catalog, 16);
+        let ranked: Vec<(i32, Option<String>, usize, usize)> = recommendations
+            .iter()
+            .map(|r| {
+                let section = r.course.section.clone();
+                (r.course.number, section, r.helps.len(), r.satisfies.len())
+            })
+            .collect();
+        assert_eq!(ranked, vec![(103, Some("B".to_string()), 1, 1)]);
+        assert_eq!(
+            recommendations[0].satisfies[0].to_string(),
+            "Problematic/major"
+        );
+    }
+}
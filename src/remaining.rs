@@ -0,0 +1,547 @@
+use crate::course::Credits;
+use crate::evaluate::{AreaOfStudy, Course, Requirement};
+use crate::expression::counter::{ExpressionCounter, Operator};
+use crate::expression::modifier::WhatEnum;
+use crate::expression::{HansonExpression, ModifierExpression, ReferenceExpression};
+use crate::path::RequirementPath;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// What an unsatisfied requirement still needs, in words
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Remaining {
+    pub requirement: RequirementPath,
+    /// Each of the things still needed, like `one of CSCI 276, 333, 336` or
+    /// `1 more course where level >= 300`
+    pub needs: Vec<String>,
+}
+
+impl fmt::Display for Remaining {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}:", self.requirement)?;
+
+        for need in &self.needs {
+            writeln!(f, "  - {}", need)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists what each of the area's unsatisfied requirements still needs, from the area itself
+/// down to its innermost requirements.
+///
+/// The area must already have been evaluated; requirements that weren't are skipped.
+pub fn remaining_requirements(area_of_study: &AreaOfStudy) -> Vec<Remaining> {
+    let path = RequirementPath::area(&area_of_study.area_name, &area_of_study.area_type);
+    let mut found = vec![];
+
+    if let Some(detail) = &area_of_study.evaluated {
+        if !detail.success {
+            found.push(Remaining {
+                requirement: path.clone(),
+                needs: unmet(&area_of_study.result),
+            });
+        }
+    }
+
+    find_remaining(&area_of_study.children, &path, &mut found);
+    found
+}
+
+fn find_remaining(
+    requirements: &[Requirement],
+    path: &RequirementPath,
+    found: &mut Vec<Remaining>,
+) {
+    for requirement in requirements {
+        let path_to_here = path.child(&requirement.name);
+
+        if let (Some(detail), Some(result)) = (&requirement.evaluated, &requirement.result) {
+            if !detail.success {
+                let needs = if detail.overridden {
+                    vec!["has been marked as unsatisfied".to_string()]
+                } else {
+                    unmet(result)
+                };
+
+                found.push(Remaining {
+                    requirement: path_to_here.clone(),
+                    needs,
+                });
+            }
+        }
+
+        find_remaining(&requirement.children, &path_to_here, found);
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    match count {
+        1 => format!("1 {}", word),
+        n => format!("{} {}s", n, word),
+    }
+}
+
+/// Whether evaluation found the expression to be satisfied
+fn succeeded(expr: &HansonExpression) -> bool {
+    let result = match expr {
+        HansonExpression::BooleanAnd(expr) => expr.result,
+        HansonExpression::BooleanOr(expr) => expr.result,
+        HansonExpression::Course(expr) => expr.result,
+        HansonExpression::Occurrence(expr) => expr.result,
+        HansonExpression::Of(expr) => expr.result,
+        HansonExpression::Reference(expr) => expr.result,
+        HansonExpression::Where(expr) => expr.result,
+        HansonExpression::Modifier(ModifierExpression::Where(expr)) => expr.result,
+        HansonExpression::Modifier(ModifierExpression::Filter(expr)) => expr.result,
+        HansonExpression::Modifier(ModifierExpression::FilterWhere(expr)) => expr.result,
+        HansonExpression::Modifier(ModifierExpression::Children(expr)) => expr.result,
+        HansonExpression::Modifier(ModifierExpression::ChildrenWhere(expr)) => expr.result,
+    };
+
+    result == Some(true)
+}
+
+/// Lists a set of alternatives, leaving out the department of a course when it's the same as
+/// the course before it, as in `CSCI 276, 333, 336`
+fn describe_options(options: &[&HansonExpression]) -> String {
+    let mut described = vec![];
+    let mut last_department: Option<&Vec<String>> = None;
+
+    for option in options {
+        match option {
+            HansonExpression::Course(course) if last_department == Some(&course.department) => {
+                let department = course.department.join("/");
+                described.push(course.to_string()[department.len() + 1..].to_string());
+            }
+            HansonExpression::Course(course) => {
+                last_department = Some(&course.department);
+                described.push(course.to_string());
+            }
+            other => {
+                last_department = None;
+                described.push(describe(other));
+            }
+        }
+    }
+
+    described.join(", ")
+}
+
+/// Describes an expression in full, regardless of how much of it has been satisfied
+fn describe(expr: &HansonExpression) -> String {
+    match expr {
+        HansonExpression::Course(course) => course.to_string(),
+        HansonExpression::Reference(reference) => format!("`{}`", reference.requirement),
+        HansonExpression::BooleanAnd(and) => {
+            let values: Vec<String> = and.values.iter().map(describe).collect();
+            format!("({})", values.join(" and "))
+        }
+        HansonExpression::BooleanOr(or) => {
+            let values: Vec<&HansonExpression> = or.values.iter().collect();
+            format!("one of ({})", describe_options(&values))
+        }
+        HansonExpression::Of(of) => {
+            let values: Vec<&HansonExpression> = of.of.iter().collect();
+            format!("{} of ({})", of.count, describe_options(&values))
+        }
+        HansonExpression::Occurrence(occurrence) => format!(
+            "{} of {}",
+            counted(&occurrence.count, "occurrence", "occurrences"),
+            occurrence.course
+        ),
+        HansonExpression::Where(expr) => format!(
+            "{} where {}",
+            counted(&expr.count, "course", "courses"),
+            expr.qualification
+        ),
+        HansonExpression::Modifier(modifier) => {
+            let (count, what, _, source) = describe_modifier(modifier);
+            let what = counted(count, what_name(what, 1), what_name(what, 2));
+            format!("{} {}", what, source)
+        }
+    }
+}
+
+/// How many of something a counter asks for, like `at least 1 course` or `exactly 2 courses`
+fn counted(count: &ExpressionCounter, one: &str, many: &str) -> String {
+    match count.num {
+        Some(1) => format!("{} {}", count, one),
+        _ => format!("{} {}", count, many),
+    }
+}
+
+fn what_name(what: &WhatEnum, count: usize) -> &'static str {
+    match (what, count) {
+        (WhatEnum::Course, 1) => "course",
+        (WhatEnum::Course, _) => "courses",
+        (WhatEnum::Credit, 1) => "credit",
+        (WhatEnum::Credit, _) => "credits",
+        (WhatEnum::Department, 1) => "department",
+        (WhatEnum::Department, _) => "departments",
+    }
+}
+
+/// Pulls apart a modifier into its counter, what it counts, the courses it has matched, and a
+/// description of where it counts them from
+fn describe_modifier(
+    modifier: &ModifierExpression,
+) -> (&ExpressionCounter, &WhatEnum, &Option<Vec<Course>>, String) {
+    let children = |children: &[ReferenceExpression]| {
+        let names: Vec<&str> = children.iter().map(|c| c.requirement.as_str()).collect();
+        format!("from ({})", names.join(", "))
+    };
+
+    match modifier {
+        ModifierExpression::Where(m) => (
+            &m.count,
+            &m.what,
+            &m.matched_courses,
+            format!("where {}", m.qualification),
+        ),
+        ModifierExpression::Filter(m) => (
+            &m.count,
+            &m.what,
+            &m.matched_courses,
+            "from the filter".to_string(),
+        ),
+        ModifierExpression::FilterWhere(m) => (
+            &m.count,
+            &m.what,
+            &m.matched_courses,
+            format!("from the filter where {}", m.qualification),
+        ),
+        ModifierExpression::Children(m) => {
+            (&m.count, &m.what, &m.matched_courses, children(&m.children))
+        }
+        ModifierExpression::ChildrenWhere(m) => (
+            &m.count,
+            &m.what,
+            &m.matched_courses,
+            format!("{} where {}", children(&m.children), m.qualification),
+        ),
+    }
+}
+
+/// How many more of something a counter needs, having matched `have` of them
+fn still_needed(count: &ExpressionCounter, have: usize) -> usize {
+    count.required(have).saturating_sub(have)
+}
+
+/// Whether a failing counter matched more than it allows, rather than too few
+fn is_exceeded(count: &ExpressionCounter, have: usize, available: usize) -> bool {
+    match count.operator {
+        Operator::Lte => true,
+        // an "exactly" counter only holds on to as many as it asked for, so having that many
+        // and still failing means that there were more
+        Operator::Eq => have >= count.required(available),
+        Operator::Gte => false,
+    }
+}
+
+/// Describes a limit that was exceeded, like `no more than 2 courses`
+fn no_more_than(limit: usize, one: &str, many: &str) -> String {
+    match limit {
+        0 => format!("no {}", many),
+        1 => format!("no more than 1 {}", one),
+        n => format!("no more than {} {}", n, many),
+    }
+}
+
+/// Describes the parts of an evaluated expression that haven't been satisfied
+fn unmet(expr: &HansonExpression) -> Vec<String> {
+    if succeeded(expr) {
+        return vec![];
+    }
+
+    match expr {
+        HansonExpression::Course(course) => vec![course.to_string()],
+        HansonExpression::Reference(reference) => {
+            vec![format!("complete `{}`", reference.requirement)]
+        }
+        HansonExpression::BooleanAnd(and) => and.values.iter().flat_map(unmet).collect(),
+        HansonExpression::BooleanOr(or) => {
+            let options: Vec<&HansonExpression> = or.values.iter().collect();
+            vec![format!("one of {}", describe_options(&options))]
+        }
+        HansonExpression::Of(of) => {
+            let options: Vec<&HansonExpression> = of.of.iter().filter(|o| !succeeded(o)).collect();
+            let have = of.of.len() - options.len();
+
+            if is_exceeded(&of.count, have, of.of.len()) {
+                let satisfied: Vec<&HansonExpression> =
+                    of.of.iter().filter(|o| succeeded(o)).collect();
+                let every: Vec<&HansonExpression> = of.of.iter().collect();
+
+                return match of.count.required(of.of.len()) {
+                    0 => vec![format!("none of {}", describe_options(&satisfied))],
+                    limit => vec![format!(
+                        "no more than {} of {}",
+                        limit,
+                        describe_options(&every)
+                    )],
+                };
+            }
+
+            match of.count.required(of.of.len()).saturating_sub(have).max(1) {
+                needed if needed == options.len() && needed > 1 => {
+                    options.iter().flat_map(|option| unmet(option)).collect()
+                }
+                1 => vec![format!("one of {}", describe_options(&options))],
+                needed => vec![format!("{} more of {}", needed, describe_options(&options))],
+            }
+        }
+        HansonExpression::Occurrence(occurrence) => {
+            let have = occurrence.matched_courses.as_ref().map_or(0, |m| m.len());
+
+            if is_exceeded(&occurrence.count, have, have) {
+                let limit = occurrence.count.required(have);
+                return vec![format!(
+                    "{} of {}",
+                    no_more_than(limit, "occurrence", "occurrences"),
+                    occurrence.course
+                )];
+            }
+
+            vec![format!(
+                "{} more of {}",
+                plural(still_needed(&occurrence.count, have).max(1), "occurrence"),
+                occurrence.course
+            )]
+        }
+        HansonExpression::Where(where_expr) => {
+            let have = where_expr.matched_courses.as_ref().map_or(0, |m| m.len());
+            let clause = &where_expr.qualification;
+
+            if is_exceeded(&where_expr.count, have, have) {
+                let limit = where_expr.count.required(have);
+                return vec![format!(
+                    "{} where {}",
+                    no_more_than(limit, "course", "courses"),
+                    clause
+                )];
+            }
+
+            vec![format!(
+                "{} where {}",
+                plural(still_needed(&where_expr.count, have).max(1), "more course"),
+                clause
+            )]
+        }
+        HansonExpression::Modifier(modifier) => {
+            let (count, what, matched, source) = describe_modifier(modifier);
+            let matched: &[Course] = matched.as_ref().map_or(&[], |m| m);
+
+            match what {
+                WhatEnum::Credit => {
+                    let needed = Credits::whole(count.num.unwrap_or(0));
+                    let have = Credits::of(matched);
+
+                    match count.operator {
+                        Operator::Lte | Operator::Eq if have > needed => vec![format!(
+                            "{} {}",
+                            no_more_than(count.num.unwrap_or(0) as usize, "credit", "credits"),
+                            source
+                        )],
+                        _ => {
+                            let missing = needed - have;
+                            let what =
+                                what_name(what, if missing == Credits::whole(1) { 1 } else { 2 });
+                            vec![format!("{} more {} {}", missing, what, source)]
+                        }
+                    }
+                }
+                // each department that counted is represented by one of the matched courses
+                WhatEnum::Course | WhatEnum::Department
+                    if is_exceeded(count, matched.len(), matched.len()) =>
+                {
+                    let limit = count.required(matched.len());
+                    vec![format!(
+                        "{} {}",
+                        no_more_than(limit, what_name(what, 1), what_name(what, 2)),
+                        source
+                    )]
+                }
+                WhatEnum::Course | WhatEnum::Department => {
+                    let needed = still_needed(count, matched.len()).max(1);
+                    vec![format!(
+                        "{} more {} {}",
+                        needed,
+                        what_name(what, needed),
+                        source
+                    )]
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::remaining_requirements;
+    use crate::course::course;
+    use crate::evaluate::{evaluate_area, FulfillmentMap, OverrideMap};
+    use crate::hanson::parse_area;
+
+    #[test]
+    fn lists_what_is_left() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: all of (Intro, Theory, Upper Level, Breadth)
+
+Intro: CSCI 121 & CSCI 125
+Theory: CSCI 276 | CSCI 333 | CSCI 336
+Upper Level: two courses where { level >= 300 }
+Breadth: two of (CSCI 241, CSCI 251, MATH 220)
+",
+        )
+        .unwrap();
+
+        let courses = vec![
+            course("CSCI", 121),
+            course("CSCI", 241),
+            course("CSCI", 350),
+        ];
+        let result = evaluate_area(
+            &courses,
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area,
+            &[],
+        );
+
+        let remaining: Vec<(String, Vec<String>)> = remaining_requirements(&result)
+            .into_iter()
+            .map(|r| (r.requirement.to_string(), r.needs))
+            .collect();
+
+        let needs = |needs: &[&str]| needs.iter().map(|n| n.to_string()).collect();
+        assert_eq!(
+            remaining,
+            vec![
+                (
+                    "Computer Science/major".to_string(),
+                    needs(&[
+                        "complete `Intro`",
+                        "complete `Theory`",
+                        "complete `Upper Level`",
+                        "complete `Breadth`"
+                    ])
+                ),
+                (
+                    "Computer Science/major/Intro".to_string(),
+                    needs(&["CSCI 125"])
+                ),
+                (
+                    "Computer Science/major/Theory".to_string(),
+                    needs(&["one of CSCI 276, 333, 336"])
+                ),
+                (
+                    "Computer Science/major/Upper Level".to_string(),
+                    needs(&["1 more course where level >= 300"])
+                ),
+                (
+                    "Computer Science/major/Breadth".to_string(),
+                    needs(&["one of CSCI 251, MATH 220"])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn describes_limits_that_were_exceeded() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: all of (Avoid, Cap, Exact, Exact Credits)
+
+Avoid: none of (CSCI 121, CSCI 125)
+Cap: at most one course where { level = 200 }
+Exact: exactly two courses where { level = 300 }
+Exact Credits: exactly two credits from courses where { level = 100 }
+",
+        )
+        .unwrap();
+
+        let courses = vec![
+            course("CSCI", 121),
+            course("CSCI", 130),
+            course("CSCI", 131),
+            course("CSCI", 241),
+            course("CSCI", 251),
+            course("CSCI", 350),
+            course("CSCI", 351),
+            course("CSCI", 352),
+        ];
+        let result = evaluate_area(
+            &courses,
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area,
+            &[],
+        );
+
+        let remaining: Vec<Vec<String>> = remaining_requirements(&result)
+            .into_iter()
+            .skip(1)
+            .map(|r| r.needs)
+            .collect();
+
+        assert_eq!(
+            remaining,
+            vec![
+                vec!["none of CSCI 121".to_string()],
+                vec!["no more than 1 course where level = 200".to_string()],
+                vec!["no more than 2 courses where level = 300".to_string()],
+                vec!["no more than 2 credits where level = 100".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_of_one_are_singular() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: all of (Upper Level, Credits, Either)
+
+Upper Level: one course where { level >= 300 }
+Credits: at least three credits from courses where { level = 100 }
+Either: CSCI 390 | one course where { level >= 400 }
+",
+        )
+        .unwrap();
+
+        let courses = vec![course("CSCI", 121), course("CSCI", 125)];
+        let result = evaluate_area(
+            &courses,
+            &OverrideMap::new(),
+            &FulfillmentMap::new(),
+            area,
+            &[],
+        );
+
+        let remaining: Vec<Vec<String>> = remaining_requirements(&result)
+            .into_iter()
+            .skip(1)
+            .map(|r| r.needs)
+            .collect();
+
+        assert_eq!(
+            remaining,
+            vec![
+                vec!["1 more course where level >= 300".to_string()],
+                vec!["1 more credit where level = 100".to_string()],
+                vec!["one of CSCI 390, at least 1 course where level >= 400".to_string()],
+            ]
+        );
+    }
+}
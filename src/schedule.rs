@@ -0,0 +1,232 @@
+use crate::course::{Course, DayOfWeek, Offering};
+use crate::error::{Error, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// One meeting of a course, with its times in minutes after midnight
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Meeting {
+    pub day: DayOfWeek,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Meeting {
+    fn overlaps(&self, other: &Meeting) -> bool {
+        self.day == other.day && self.start < other.end && other.start < self.end
+    }
+}
+
+/// Two courses in the same term that meet at the same time
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Conflict {
+    pub first: Course,
+    pub second: Course,
+    pub day: DayOfWeek,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} and {} {} both meet on {:?} in {}.{}",
+            self.first.department.join("/"),
+            self.first.number,
+            self.second.department.join("/"),
+            self.second.number,
+            self.day,
+            self.first.year,
+            self.first.semester
+        )
+    }
+}
+
+/// Parses a time written like `0905`, `905` or `9:05` into minutes after midnight
+pub fn parse_time(input: &str) -> Option<u32> {
+    if !input.is_ascii() {
+        return None;
+    }
+
+    let (hours, minutes) = match input.find(':') {
+        Some(index) => (&input[..index], &input[index + 1..]),
+        None if input.len() >= 3 && input.len() <= 4 => input.split_at(input.len() - 2),
+        None => return None,
+    };
+
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if hours.is_empty() || hours.len() > 2 || minutes.len() != 2 {
+        return None;
+    }
+    if !is_digits(hours) || !is_digits(minutes) {
+        return None;
+    }
+
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+
+    if hours < 24 && minutes < 60 {
+        Some(hours * 60 + minutes)
+    } else {
+        None
+    }
+}
+
+/// Reads the meeting times of one of a course's offerings
+pub fn meeting(course: &Course, offering: &Offering) -> Result<Meeting> {
+    let time = |input: &str| {
+        parse_time(input).ok_or_else(|| Error::MalformedOffering {
+            path: None,
+            clbid: course.clbid.clone(),
+            message: format!("`{}` isn't a time like `0905`", input),
+        })
+    };
+
+    let start = time(&offering.start)?;
+    let end = time(&offering.end)?;
+
+    if end <= start {
+        return Err(Error::MalformedOffering {
+            path: None,
+            clbid: course.clbid.clone(),
+            message: format!(
+                "it ends at {}, before it starts at {}",
+                offering.end, offering.start
+            ),
+        });
+    }
+
+    Ok(Meeting {
+        day: offering.day.clone(),
+        start,
+        end,
+    })
+}
+
+/// Reads the meeting times of every offering of every course, to catch malformed times in a
+/// catalog before it's used
+pub fn check_offerings(courses: &[Course]) -> Result<()> {
+    for course in courses {
+        for offering in &course.offerings {
+            meeting(course, offering)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The meetings of a course; offerings with malformed times are left out, since
+/// [`check_offerings`](fn.check_offerings.html) reports those
+fn meetings(course: &Course) -> Vec<Meeting> {
+    course
+        .offerings
+        .iter()
+        .filter_map(|offering| meeting(course, offering).ok())
+        .collect()
+}
+
+/// The day on which two courses meet at the same time, if they're taken in the same term and
+/// their meetings overlap
+pub fn clash(a: &Course, b: &Course) -> Option<DayOfWeek> {
+    if (a.year, a.semester) != (b.year, b.semester) {
+        return None;
+    }
+
+    let theirs = meetings(b);
+    meetings(a)
+        .into_iter()
+        .find(|mine| theirs.iter().any(|other| mine.overlaps(other)))
+        .map(|mine| mine.day)
+}
+
+/// Finds every pair of courses that are taken in the same term and meet at the same time
+pub fn conflicts(courses: &[Course]) -> Vec<Conflict> {
+    let mut found = vec![];
+
+    for (i, first) in courses.iter().enumerate() {
+        for second in &courses[i + 1..] {
+            if let Some(day) = clash(first, second) {
+                found.push(Conflict {
+                    first: first.clone(),
+                    second: second.clone(),
+                    day,
+                });
+            }
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_offerings, conflicts, parse_time};
+    use crate::course::{course, Course, DayOfWeek, Offering};
+
+    fn meets(times: &[(DayOfWeek, &str, &str)]) -> Vec<Offering> {
+        times
+            .iter()
+            .map(|(day, start, end)| Offering {
+                day: day.clone(),
+                start: start.to_string(),
+                end: end.to_string(),
+                location: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("0905"), Some(9 * 60 + 5));
+        assert_eq!(parse_time("905"), Some(9 * 60 + 5));
+        assert_eq!(parse_time("13:30"), Some(13 * 60 + 30));
+        assert_eq!(parse_time("0975"), None);
+        assert_eq!(parse_time("2500"), None);
+        assert_eq!(parse_time("noon"), None);
+        assert_eq!(parse_time("12:5"), None);
+        assert_eq!(parse_time("1:230"), None);
+        assert_eq!(parse_time("9é5"), None);
+    }
+
+    #[test]
+    fn finds_overlapping_meetings() {
+        let courses = vec![
+            Course {
+                offerings: meets(&[
+                    (DayOfWeek::Mo, "0905", "1000"),
+                    (DayOfWeek::We, "0905", "1000"),
+                ]),
+                ..course("PHIL", 101)
+            },
+            // starts just as 101 ends
+            Course {
+                offerings: meets(&[(DayOfWeek::Mo, "1000", "1100")]),
+                ..course("PHIL", 102)
+            },
+            Course {
+                offerings: meets(&[(DayOfWeek::We, "0930", "1045")]),
+                ..course("PHIL", 103)
+            },
+            // the same time as 101, but in another term
+            Course {
+                offerings: meets(&[(DayOfWeek::Mo, "0905", "1000")]),
+                ..course("PHIL", 104).in_term(2015, 2)
+            },
+        ];
+
+        let found: Vec<String> = conflicts(&courses).iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            found,
+            vec!["PHIL 101 and PHIL 103 both meet on We in 2015.1"]
+        );
+
+        let backwards = Course {
+            offerings: meets(&[(DayOfWeek::Fr, "1100", "1000")]),
+            ..course("PHIL", 105)
+        };
+        let err = check_offerings(&[backwards]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "malformed offering for course PHIL105: it ends at 1000, before it starts at 1100"
+        );
+    }
+}
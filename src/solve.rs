@@ -0,0 +1,359 @@
+use crate::claim::Claim;
+use crate::compute::candidate_courses;
+use crate::evaluate::{
+    apply_filter, evaluate_area, AreaOfStudy, Course, FulfillmentMap, OverrideMap, Requirement,
+};
+use crate::path::RequirementPath;
+use crate::progress::Progress;
+use serde_derive::{Deserialize, Serialize};
+
+/// How many assignments of contested courses to try by default
+pub const DEFAULT_BUDGET: usize = 256;
+
+/// One way of handing out the contested courses, and how the area fared with it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Alternative {
+    /// The courses that were set aside for a particular requirement
+    pub reservations: Vec<Claim>,
+    pub success: bool,
+    pub progress: Progress,
+}
+
+/// Whether the area passed, and otherwise how far its requirements got on average
+fn score(area: &AreaOfStudy) -> (bool, ordered_float::OrderedFloat<f64>) {
+    let success = match &area.evaluated {
+        Some(detail) => detail.success,
+        None => false,
+    };
+
+    let fractions: Vec<f64> = area
+        .children
+        .iter()
+        .filter_map(|r| r.evaluated.as_ref())
+        .map(|detail| detail.progress.fraction)
+        .collect();
+    let average = if fractions.is_empty() {
+        0.0
+    } else {
+        fractions.iter().sum::<f64>() / fractions.len() as f64
+    };
+
+    (success, average.into())
+}
+
+/// A requirement's claim to some of the student's courses.
+///
+/// Requirements whose parent lets its children share courses are grouped under that parent, since
+/// a course reserved for the parent stays available to everything beneath it.
+struct Candidate {
+    claimant: RequirementPath,
+    courses: Vec<Course>,
+}
+
+/// Collects, for every requirement, the courses that its result could possibly use
+fn find_candidates(
+    requirements: &[Requirement],
+    path: &RequirementPath,
+    group: Option<&RequirementPath>,
+    courses: &[Course],
+    found: &mut Vec<Candidate>,
+) {
+    for req in requirements {
+        let path_to_here = path.child(&req.name);
+        let claimant = match group {
+            Some(group) => group.clone(),
+            None => path_to_here.clone(),
+        };
+
+        if let Some(result) = &req.result {
+            let mut pool = courses.to_vec();
+            if let Some(filter) = &req.filter {
+                pool = apply_filter(&mut filter.clone(), pool);
+            }
+
+            found.push(Candidate {
+                claimant: claimant.clone(),
+                courses: candidate_courses(result, &pool),
+            });
+        }
+
+        let shares = req.children_share_courses.unwrap_or(false);
+        let group = if shares { Some(&claimant) } else { None };
+        find_candidates(&req.children, &path_to_here, group, courses, found);
+    }
+}
+
+/// The courses that more than one requirement could use, in the order the student took them,
+/// each with the requirements that could use it
+fn contested_courses(
+    courses: &[Course],
+    candidates: &[Candidate],
+) -> Vec<(Course, Vec<RequirementPath>)> {
+    let mut contested: Vec<(Course, Vec<RequirementPath>)> = vec![];
+
+    for course in courses {
+        if contested.iter().any(|(c, _)| c == course) {
+            continue;
+        }
+
+        let mut claimants: Vec<RequirementPath> = vec![];
+        for candidate in candidates.iter().filter(|c| c.courses.contains(course)) {
+            if !claimants.contains(&candidate.claimant) {
+                claimants.push(candidate.claimant.clone());
+            }
+        }
+
+        // a course reserved for a requirement is still available to its ancestors and
+        // descendants, so they never compete for it
+        let competing = claimants
+            .iter()
+            .any(|a| claimants.iter().any(|b| !a.is_within(b) && !b.is_within(a)));
+
+        if competing {
+            contested.push((course.clone(), claimants));
+        }
+    }
+
+    contested
+}
+
+/// Whether the requirement at `path`, or one of its descendants, claimed `course` in an attempt
+fn claimed_by(
+    requirements: &[Requirement],
+    parent: &RequirementPath,
+    path: &RequirementPath,
+    course: &Course,
+) -> bool {
+    requirements.iter().any(|req| {
+        let path_to_here = parent.child(&req.name);
+
+        let claimed_here = match &req.evaluated {
+            Some(detail) => path_to_here.is_within(path) && detail.claimed_courses.contains(course),
+            None => false,
+        };
+
+        claimed_here || claimed_by(&req.children, &path_to_here, path, course)
+    })
+}
+
+/// Queues the assignments that hand the contested course at `depth` to each of its claimants.
+///
+/// Handing a course to the requirement that already claimed it in `attempt` wouldn't change the
+/// outcome, so rather than being evaluated again, that assignment goes straight on to the next
+/// contested course.
+fn branch(
+    attempt: &AreaOfStudy,
+    root: &RequirementPath,
+    contested: &[(Course, Vec<RequirementPath>)],
+    depth: usize,
+    reservations: &[Claim],
+    pending: &mut Vec<(usize, Vec<Claim>)>,
+) {
+    let (course, claimants) = match contested.get(depth) {
+        Some(entry) => entry,
+        None => return,
+    };
+
+    // pushed in reverse, so that the first claimant is the next one to be tried
+    for claimant in claimants.iter().rev() {
+        let mut next = reservations.to_vec();
+        next.push(Claim {
+            course: course.clone(),
+            claimant: claimant.clone(),
+        });
+
+        if claimed_by(&attempt.children, root, claimant, course) {
+            branch(attempt, root, contested, depth + 1, &next, pending);
+        } else {
+            pending.push((depth + 1, next));
+        }
+    }
+}
+
+/// Searches for an assignment of the student's courses to requirements that satisfies the area.
+///
+/// A course is contested when more than one requirement could use it, and those requirements
+/// don't share courses. The search starts from the first-come, first-served assignment, and then
+/// backtracks through every way of handing each contested course to one of the requirements
+/// that could use it, depth-first, until the area succeeds or `budget` assignments have been
+/// evaluated. The best evaluation found is returned, and every assignment that was tried is
+/// listed in its `alternatives`.
+pub fn solve_area(
+    courses: &[Course],
+    overrides: &OverrideMap,
+    fulfillments: &FulfillmentMap,
+    area_of_study: AreaOfStudy,
+    budget: usize,
+) -> AreaOfStudy {
+    let root = RequirementPath::area(&area_of_study.area_name, &area_of_study.area_type);
+
+    let mut candidates = vec![];
+    find_candidates(
+        &area_of_study.children,
+        &root,
+        None,
+        courses,
+        &mut candidates,
+    );
+    let contested = contested_courses(courses, &candidates);
+
+    let mut alternatives: Vec<Alternative> = vec![];
+    let mut best: Option<AreaOfStudy> = None;
+
+    let mut pending: Vec<(usize, Vec<Claim>)> = vec![(0, vec![])];
+
+    while let Some((depth, reservations)) = pending.pop() {
+        if alternatives.len() >= budget.max(1) {
+            break;
+        }
+
+        let attempt = evaluate_area(
+            courses,
+            overrides,
+            fulfillments,
+            area_of_study.clone(),
+            &reservations,
+        );
+
+        let (success, progress) = match &attempt.evaluated {
+            Some(detail) => (detail.success, detail.progress),
+            None => (false, Progress::default()),
+        };
+        alternatives.push(Alternative {
+            reservations: reservations.clone(),
+            success,
+            progress,
+        });
+
+        branch(
+            &attempt,
+            &root,
+            &contested,
+            depth,
+            &reservations,
+            &mut pending,
+        );
+
+        let is_better = match &best {
+            Some(best) => score(&attempt) > score(best),
+            None => true,
+        };
+
+        if is_better {
+            best = Some(attempt);
+        }
+
+        if success {
+            break;
+        }
+    }
+
+    let mut best = best.expect("the search always makes at least one attempt");
+    if let Some(detail) = &mut best.evaluated {
+        detail.alternatives = alternatives;
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::solve_area;
+    use crate::course::course;
+    use crate::hanson::parse_area;
+    use std::collections::BTreeMap;
+
+    const CONFLICT: &str = "
+name: Problematic
+type: major
+revision: 2011-12
+result: Req 1 & Req 2
+
+Req 1: PHIL 101 | PHIL 102
+Req 2: PHIL 101 & PHIL 103
+";
+
+    #[test]
+    fn finds_an_assignment_that_satisfies_the_area() {
+        let courses = vec![
+            course("PHIL", 101),
+            course("PHIL", 102),
+            course("PHIL", 103),
+        ];
+        let area = parse_area(CONFLICT).unwrap();
+
+        let result = solve_area(&courses, &BTreeMap::new(), &BTreeMap::new(), area, 10);
+        let detail = result.evaluated.unwrap();
+
+        assert!(detail.success);
+        assert_eq!(detail.alternatives.len(), 2);
+        assert!(!detail.alternatives[0].success);
+        assert_eq!(
+            detail.alternatives[1].reservations[0].claimant.to_string(),
+            "Problematic/major/Req 2"
+        );
+    }
+
+    #[test]
+    fn gives_up_when_the_budget_runs_out() {
+        let courses = vec![
+            course("PHIL", 101),
+            course("PHIL", 102),
+            course("PHIL", 103),
+        ];
+        let area = parse_area(CONFLICT).unwrap();
+
+        let result = solve_area(&courses, &BTreeMap::new(), &BTreeMap::new(), area, 1);
+        let detail = result.evaluated.unwrap();
+
+        assert!(!detail.success);
+        assert_eq!(detail.progress.requirements, (1, 2));
+        assert_eq!(detail.alternatives.len(), 1);
+    }
+
+    #[test]
+    fn backtracks_past_the_first_choice_for_each_course() {
+        let courses = vec![
+            course("CSCI", 101),
+            course("CSCI", 102),
+            course("CSCI", 103),
+            course("CSCI", 104),
+        ];
+        let area = parse_area(
+            "
+name: Tangled
+type: major
+revision: 2011-12
+result: R0 & R1 & R2 & R3
+
+R0: CSCI 101 | CSCI 104 | CSCI 102
+R1: CSCI 103
+R2: CSCI 104 | CSCI 103 | CSCI 101
+R3: CSCI 103 | CSCI 104
+",
+        )
+        .unwrap();
+
+        let result = solve_area(&courses, &BTreeMap::new(), &BTreeMap::new(), area, 256);
+        assert!(result.evaluated.unwrap().success);
+
+        let claimed: Vec<(String, Vec<i32>)> = result
+            .children
+            .iter()
+            .map(|r| {
+                let detail = r.evaluated.as_ref().unwrap();
+                let numbers = detail.claimed_courses.iter().map(|c| c.number).collect();
+                (r.name.clone(), numbers)
+            })
+            .collect();
+        assert_eq!(
+            claimed,
+            vec![
+                ("R0".to_string(), vec![102]),
+                ("R1".to_string(), vec![103]),
+                ("R2".to_string(), vec![101]),
+                ("R3".to_string(), vec![104]),
+            ]
+        );
+    }
+}
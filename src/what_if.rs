@@ -0,0 +1,228 @@
+use crate::audit;
+use crate::error::{Error, Result};
+use crate::evaluate::{AreaOfStudy, Course, Requirement};
+use crate::expression::CourseExpression;
+use crate::hanson::{parse_course, ParseError};
+use crate::parse::Student;
+use crate::path::RequirementPath;
+use serde_derive::{Deserialize, Serialize};
+
+/// How an area of study would turn out if the student took some more courses
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WhatIf {
+    /// The area, evaluated against the courses the student has taken
+    pub before: AreaOfStudy,
+    /// The area, evaluated with the hypothetical courses added
+    pub after: AreaOfStudy,
+    /// The requirements that fail now but would pass, the area itself included
+    pub newly_satisfied: Vec<RequirementPath>,
+    /// The requirements that pass now but would fail, because the new courses changed which
+    /// requirement got to use which course
+    pub newly_unsatisfied: Vec<RequirementPath>,
+}
+
+/// The term after the latest one that the student has taken a course in, as `(year, semester)`.
+///
+/// Fall, interim and spring are semesters 1 through 3 of a school year; the summer sessions are
+/// skipped over.
+pub fn next_term(courses: &[Course]) -> Option<(i32, i32)> {
+    courses
+        .iter()
+        .map(|c| (c.year, c.semester))
+        .max()
+        .map(|(year, semester)| match semester {
+            1 | 2 => (year, semester + 1),
+            _ => (year + 1, 1),
+        })
+}
+
+/// Makes up a course record for a course that the student hasn't taken yet.
+///
+/// A term written in the expression, as in `CSCI 253.2019.3`, takes precedence over `term`.
+pub fn hypothetical_course(expr: &CourseExpression, term: (i32, i32)) -> Course {
+    let (year, semester) = match (expr.year, expr.semester) {
+        (Some(year), Some(semester)) => (year, semester),
+        _ => term,
+    };
+
+    let id = format!("hypothetical {}", expr);
+
+    Course {
+        clbid: id.clone(),
+        credits: ordered_float::OrderedFloat(1.0),
+        crsid: id,
+        department: expr.department.clone(),
+        level: expr.level.unwrap_or(expr.number / 100 * 100),
+        number: expr.number,
+        semester,
+        year,
+        term: Some(year * 10 + semester),
+        course_type: match expr.lab {
+            Some(true) => Some("Lab".to_string()),
+            _ => None,
+        },
+        ..Course::default()
+    }
+}
+
+/// Parses a course written like `CSCI 253` into a hypothetical course record
+pub fn parse_hypothetical(input: &str, term: (i32, i32)) -> Result<Course> {
+    parse_course(input)
+        .map(|expr| hypothetical_course(&expr, term))
+        .map_err(|error| Error::Hanson {
+            path: None,
+            error: Box::new(ParseError {
+                message: format!("in `{}`: {}", input, error.message),
+                ..error
+            }),
+        })
+}
+
+/// How a requirement, or the area itself, came out of an evaluation
+#[derive(Debug, Clone)]
+pub(crate) struct Outcome {
+    pub path: RequirementPath,
+    pub success: bool,
+    pub fraction: f64,
+}
+
+fn collect_outcomes(
+    requirements: &[Requirement],
+    path: &RequirementPath,
+    found: &mut Vec<Outcome>,
+) {
+    for requirement in requirements {
+        let path_to_here = path.child(&requirement.name);
+
+        if let Some(detail) = &requirement.evaluated {
+            found.push(Outcome {
+                path: path_to_here.clone(),
+                success: detail.success,
+                fraction: detail.progress.fraction,
+            });
+        }
+
+        collect_outcomes(&requirement.children, &path_to_here, found);
+    }
+}
+
+/// How the area and each of its requirements came out, parents before their children
+pub(crate) fn outcomes(area_of_study: &AreaOfStudy) -> Vec<Outcome> {
+    let path = RequirementPath::area(&area_of_study.area_name, &area_of_study.area_type);
+    let mut found = vec![];
+
+    if let Some(detail) = &area_of_study.evaluated {
+        found.push(Outcome {
+            path: path.clone(),
+            success: detail.success,
+            fraction: detail.progress.fraction,
+        });
+    }
+
+    collect_outcomes(&area_of_study.children, &path, &mut found);
+    found
+}
+
+/// Evaluates the area twice, once as the student stands and once with the hypothetical
+/// courses added to their record, and compares the two
+pub fn what_if(
+    area_of_study: AreaOfStudy,
+    student: &Student,
+    hypothetical: &[Course],
+    budget: usize,
+) -> WhatIf {
+    let before = audit(area_of_study.clone(), student, budget);
+
+    let mut with_courses = student.clone();
+    with_courses.courses.extend_from_slice(hypothetical);
+    let after = audit(area_of_study, &with_courses, budget);
+
+    let was = outcomes(&before);
+    let mut newly_satisfied = vec![];
+    let mut newly_unsatisfied = vec![];
+
+    for outcome in outcomes(&after) {
+        let was_satisfied = was.iter().any(|o| o.path == outcome.path && o.success);
+
+        match (was_satisfied, outcome.success) {
+            (false, true) => newly_satisfied.push(outcome.path),
+            (true, false) => newly_unsatisfied.push(outcome.path),
+            _ => {}
+        }
+    }
+
+    WhatIf {
+        before,
+        after,
+        newly_satisfied,
+        newly_unsatisfied,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{next_term, parse_hypothetical, what_if};
+    use crate::course::course;
+    use crate::evaluate::{FulfillmentMap, OverrideMap};
+    use crate::hanson::parse_area;
+    use crate::parse::Student;
+
+    #[test]
+    fn parses_hypothetical_courses() {
+        let course = parse_hypothetical("CSCI 253", (2019, 1)).unwrap();
+        assert_eq!((course.year, course.semester, course.level), (2019, 1, 200));
+
+        let course = parse_hypothetical("PHYS 130L.2020.3", (2019, 1)).unwrap();
+        assert_eq!((course.year, course.semester), (2020, 3));
+        assert!(course.is_lab());
+
+        let err = parse_hypothetical("CSCI 253 | CSCI 263", (2019, 1)).unwrap_err();
+        assert!(err.to_string().contains("`CSCI 253 | CSCI 263`"), "{}", err);
+
+        assert_eq!(next_term(&[]), None);
+    }
+
+    #[test]
+    fn reports_requirements_that_would_pass() {
+        let area = parse_area(
+            "
+name: Computer Science
+type: major
+revision: 2018-19
+result: Intro & Core
+
+Intro: CSCI 121
+Core: CSCI 253 & CSCI 263
+",
+        )
+        .unwrap();
+
+        let student = Student {
+            courses: vec![
+                course("CSCI", 121).in_term(2018, 3),
+                course("CSCI", 253).in_term(2018, 3),
+            ],
+            overrides: OverrideMap::new(),
+            fulfillments: FulfillmentMap::new(),
+        };
+
+        let term = next_term(&student.courses).unwrap();
+        assert_eq!(term, (2019, 1));
+
+        let hypothetical = vec![parse_hypothetical("CSCI 263", term).unwrap()];
+        let result = what_if(area, &student, &hypothetical, 16);
+
+        let names: Vec<String> = result
+            .newly_satisfied
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Computer Science/major", "Computer Science/major/Core"]
+        );
+        assert!(result.newly_unsatisfied.is_empty());
+        assert!(!result.before.evaluated.unwrap().success);
+        assert!(result.after.evaluated.unwrap().success);
+    }
+}
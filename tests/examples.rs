@@ -0,0 +1,54 @@
+//! Audits every example student against its area, both as Hanson source and as expanded JSON,
+//! and checks that each `success.yaml` passes and each `failure.yaml` fails.
+
+use gobbldygook_examine_rust::{audit, load_area, load_student, DEFAULT_BUDGET};
+use std::fs;
+use std::path::Path;
+
+fn passes(area: &Path, student: &Path) -> bool {
+    let area = load_area(area).unwrap_or_else(|err| panic!("{}", err));
+    let student = load_student(student).unwrap_or_else(|err| panic!("{}", err));
+
+    match audit(area, &student, DEFAULT_BUDGET).evaluated {
+        Some(detail) => detail.success,
+        None => false,
+    }
+}
+
+#[test]
+fn examples_pass_and_fail_as_expected() {
+    let mut examples: Vec<_> = fs::read_dir("examples")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    examples.sort();
+
+    let mut audited = 0;
+    for example in &examples {
+        for source in &["source.yaml", "source.json"] {
+            let area = example.join(source);
+            if !area.exists() {
+                continue;
+            }
+
+            for &(student, expected) in &[("success.yaml", true), ("failure.yaml", false)] {
+                let student = example.join(student);
+                if !student.exists() {
+                    continue;
+                }
+
+                assert_eq!(
+                    passes(&area, &student),
+                    expected,
+                    "auditing {} against {}",
+                    student.display(),
+                    area.display()
+                );
+                audited += 1;
+            }
+        }
+    }
+
+    assert!(audited > 0, "no examples were found");
+}